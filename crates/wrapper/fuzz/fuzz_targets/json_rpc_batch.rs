@@ -0,0 +1,27 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_stdio_wrapper::json_rpc_batch::{
+    expects_response, is_batch, is_response, join_batch, split_batch,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = is_response(data);
+    if !is_batch(data) {
+        return;
+    }
+    let Ok(entries) = split_batch(data) else {
+        return;
+    };
+    for entry in &entries {
+        let _ = expects_response(entry);
+    }
+    if let Some(joined) = join_batch(&entries) {
+        // re-joining what was split must stay a batch of the same length
+        assert!(is_batch(&joined));
+        assert_eq!(
+            split_batch(&joined).map(|e| e.len()).ok(),
+            Some(entries.len())
+        );
+    }
+});
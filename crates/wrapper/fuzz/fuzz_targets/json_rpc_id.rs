@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_stdio_wrapper::json_rpc_id::parse_id;
+use mcp_stdio_wrapper::json_rpc_id_fast::{parse_field_fast, parse_id_fast};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_id_fast(data);
+    let _ = parse_field_fast(data, "method");
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = parse_id(text);
+    }
+});
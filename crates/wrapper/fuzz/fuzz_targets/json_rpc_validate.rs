@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_stdio_wrapper::json_rpc_validate::validate_message;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = validate_message(data);
+});
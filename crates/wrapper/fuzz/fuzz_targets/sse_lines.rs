@@ -0,0 +1,23 @@
+#![no_main]
+
+use bytes::BytesMut;
+use libfuzzer_sys::fuzz_target;
+use mcp_stdio_wrapper::sse_events::SseParser;
+use mcp_stdio_wrapper::streamer_lines::extract_lines;
+
+fuzz_target!(|data: &[u8]| {
+    // feed the input in two chunks to exercise lines split across reads
+    let mid = data.len() / 2;
+    let mut buffer = BytesMut::new();
+    let mut lines = Vec::new();
+    let mut parser = SseParser::default();
+    for chunk in [&data[..mid], &data[mid..]] {
+        buffer.extend_from_slice(chunk);
+        extract_lines(&mut buffer, &mut lines);
+    }
+    for line in &lines {
+        assert!(!line.is_empty());
+        assert!(!line.contains(&b'\n'));
+        let _ = parser.feed(line);
+    }
+});
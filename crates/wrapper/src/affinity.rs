@@ -0,0 +1,42 @@
+use crate::config::Config;
+use crate::http_client::get_http_client;
+use reqwest::Client;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// one HTTP connection that carries every request of an established session,
+/// for gateways that bind a streamable session to its connection
+#[derive(Debug)]
+pub struct SessionAffinity {
+    client: Client,
+    /// HTTP/1.1 keeps one connection only while requests take turns
+    lane: Option<Mutex<()>>,
+}
+
+impl SessionAffinity {
+    /// builds a single-connection client from `config`
+    /// # Errors
+    /// * see [`get_http_client`]
+    pub async fn new(config: &Config) -> Result<Self, String> {
+        let mut single = config.clone();
+        single.http_pool_size = Some(1);
+        Ok(Self {
+            client: get_http_client(&single).await?,
+            // HTTP/2 multiplexes concurrent requests over the one connection
+            lane: (!config.http2).then(|| Mutex::new(())),
+        })
+    }
+
+    /// client bound to the session connection
+    #[must_use]
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// waits for the connection, hold the guard until the response is read
+    pub async fn acquire(&self) -> Option<MutexGuard<'_, ()>> {
+        match &self.lane {
+            Some(lane) => Some(lane.lock().await),
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bytes::{Bytes, BytesMut};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// idle buffers kept by the process-wide pool
+pub const DEFAULT_POOL_BUFFERS: usize = 256;
+/// capacity of freshly allocated buffers, enough for most JSON-RPC requests
+pub const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+/// buffers grown beyond this are freed instead of kept
+pub const MAX_RETAINED_CAPACITY: usize = 1024 * 1024;
+
+static POOL: OnceLock<BufferPool> = OnceLock::new();
+
+/// free list of line buffers, so stdin lines do not cost an allocation each
+#[derive(Debug)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<BytesMut>>,
+    max_buffers: usize,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    recycled: AtomicU64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct PoolSnapshot {
+    /// buffers taken from the free list
+    pub hits: u64,
+    /// buffers allocated because the free list was empty
+    pub misses: u64,
+    /// buffers handed back after use
+    pub recycled: u64,
+    /// hits in percent of all requests for a buffer
+    pub hit_rate_pct: u64,
+}
+
+impl BufferPool {
+    #[must_use]
+    pub fn new(max_buffers: usize, capacity: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::with_capacity(max_buffers)),
+            max_buffers,
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            recycled: AtomicU64::new(0),
+        }
+    }
+
+    /// an empty buffer, reused when one is idle
+    #[must_use]
+    pub fn get(&self) -> BytesMut {
+        let reused = self.buffers.lock().ok().and_then(|mut b| b.pop());
+        if let Some(buffer) = reused {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return buffer;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        BytesMut::with_capacity(self.capacity)
+    }
+
+    /// hands a buffer back; oversized buffers and those beyond `max_buffers` are freed
+    pub fn put(&self, mut buffer: BytesMut) {
+        if buffer.capacity() > MAX_RETAINED_CAPACITY || buffer.capacity() < self.capacity {
+            return;
+        }
+        buffer.clear();
+        if let Ok(mut buffers) = self.buffers.lock()
+            && buffers.len() < self.max_buffers
+        {
+            buffers.push(buffer);
+            self.recycled.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// hands back the buffer behind `bytes` if nothing else still refers to it
+    pub fn recycle(&self, bytes: Bytes) {
+        if let Ok(buffer) = bytes.try_into_mut() {
+            self.put(buffer);
+        }
+    }
+
+    /// idle buffers
+    #[must_use]
+    pub fn idle(&self) -> usize {
+        self.buffers.lock().map(|b| b.len()).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> PoolSnapshot {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        PoolSnapshot {
+            hits,
+            misses,
+            recycled: self.recycled.load(Ordering::Relaxed),
+            hit_rate_pct: (hits * 100).checked_div(hits + misses).unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_POOL_BUFFERS, DEFAULT_BUFFER_CAPACITY)
+    }
+}
+
+/// process-wide pool shared by the stdin reader and the workers
+#[must_use]
+pub fn buffer_pool() -> &'static BufferPool {
+    POOL.get_or_init(BufferPool::default)
+}
@@ -0,0 +1,184 @@
+use crate::config::{Config, sanitize_url_for_debug};
+use crate::http_client::get_http_client;
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::fmt::Write;
+use std::time::Instant;
+
+/// argv[1] that switches the binary into check mode
+pub const CHECK_COMMAND: &str = "check";
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const INITIALIZED: &str = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+
+/// outcome of a single check step
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckStep {
+    pub name: &'static str,
+    pub ok: bool,
+    pub ms: u64,
+    pub detail: String,
+}
+
+/// result of `check`, printed as JSON on stdout
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    pub ok: bool,
+    pub url: Vec<String>,
+    pub server: Option<Value>,
+    pub protocol_version: Option<String>,
+    pub tools: Option<usize>,
+    pub steps: Vec<CheckStep>,
+}
+
+impl CheckReport {
+    /// process exit code, 0 when every step passed
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        i32::from(!self.ok)
+    }
+
+    fn step(&mut self, name: &'static str, started: Instant, result: Result<String, String>) {
+        let ok = result.is_ok();
+        self.ok &= ok;
+        self.steps.push(CheckStep {
+            name,
+            ok,
+            ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            detail: result.unwrap_or_else(|e| e),
+        });
+    }
+
+    /// human-readable summary
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut out = format!("Checking {}\n", self.url.join(", "));
+        for step in &self.steps {
+            let mark = if step.ok { "ok  " } else { "FAIL" };
+            let _ = writeln!(
+                out,
+                "  [{mark}] {:<12} {:>6} ms  {}",
+                step.name, step.ms, step.detail
+            );
+        }
+        out.push_str(if self.ok {
+            "Upstream is reachable\n"
+        } else {
+            "Upstream check failed\n"
+        });
+        out
+    }
+}
+
+/// one-shot connectivity test: initialize followed by tools/list
+pub async fn run_check(mut config: Config) -> CheckReport {
+    // always talk to the server, never answer from saved state
+    config.session_file = None;
+    config.list_cache_ttl = 0;
+
+    let mut report = CheckReport {
+        ok: true,
+        url: config
+            .mcp_server_url
+            .iter()
+            .map(|u| sanitize_url_for_debug(u))
+            .collect(),
+        server: None,
+        protocol_version: None,
+        tools: None,
+        steps: Vec::new(),
+    };
+
+    let started = Instant::now();
+    let setup = match get_http_client(&config).await {
+        Ok(client) => McpStreamClient::try_new(config)
+            .map(|mcp| (client, mcp))
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    let (client, mcp) = match setup {
+        Ok(setup) => setup,
+        Err(e) => {
+            report.step("configure", started, Err(e));
+            return report;
+        }
+    };
+
+    let started = Instant::now();
+    let init = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {"name": "mcp-stdio-wrapper-check", "version": env!("CARGO_PKG_VERSION")},
+        },
+    });
+    let result = match request(&mcp, &client, &init).await {
+        Ok(result) => {
+            report.server = result.get("serverInfo").cloned();
+            report.protocol_version = result
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let name = report
+                .server
+                .as_ref()
+                .and_then(|s| s.get("name"))
+                .and_then(Value::as_str)
+                .unwrap_or("unknown server");
+            Ok(format!(
+                "{name}, protocol {}",
+                report.protocol_version.as_deref().unwrap_or("unknown")
+            ))
+        }
+        Err(e) => Err(e),
+    };
+    let initialized = result.is_ok();
+    report.step("initialize", started, result);
+    if !initialized {
+        return report;
+    }
+    let _ = mcp
+        .stream_post_timeout(&client, Bytes::from(INITIALIZED))
+        .await;
+
+    let started = Instant::now();
+    let list = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+    let result = request(&mcp, &client, &list).await.map(|result| {
+        let tools = result
+            .get("tools")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len);
+        report.tools = Some(tools);
+        format!("{tools} tools")
+    });
+    report.step("tools/list", started, result);
+    report
+}
+
+/// sends a request with `--request-timeout` and the retry policy applied
+/// and returns its `result`
+async fn request(mcp: &McpStreamClient, client: &Client, request: &Value) -> Result<Value, String> {
+    let res = mcp
+        .post_with_retry(client, Bytes::from(request.to_string()))
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut response = res
+        .messages()
+        .iter()
+        .filter_map(|msg| serde_json::from_slice::<Value>(msg).ok())
+        .find(|msg| msg.get("id") == request.get("id"))
+        .ok_or("No response from server")?;
+    if let Some(error) = response.get("error") {
+        return Err(format!("Server error: {error}"));
+    }
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| "Response without result".to_string())
+}
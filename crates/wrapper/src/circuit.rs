@@ -0,0 +1,84 @@
+use crate::post_error::PostError;
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// fails fast after repeated upstream outages until a probe succeeds
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    pub(crate) probe_interval: Duration,
+    failures: AtomicU32,
+    open: AtomicBool,
+    probing: AtomicBool,
+}
+
+impl CircuitBreaker {
+    /// `threshold` of 0 disables the breaker
+    #[must_use]
+    pub fn new(threshold: u32, probe_interval: Duration) -> Self {
+        Self {
+            threshold,
+            probe_interval,
+            failures: AtomicU32::new(0),
+            open: AtomicBool::new(false),
+            probing: AtomicBool::new(false),
+        }
+    }
+
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::Acquire)
+    }
+
+    #[must_use]
+    pub fn failures(&self) -> u32 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+        if self.open.swap(false, Ordering::AcqRel) {
+            info!("Upstream reachable again, circuit closed");
+        }
+    }
+
+    /// Returns `true` if this failure opened the circuit
+    pub fn record_failure(&self) -> bool {
+        if self.threshold == 0 {
+            return false;
+        }
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= self.threshold && !self.open.swap(true, Ordering::AcqRel) {
+            warn!("Upstream failed {failures} times in a row, circuit opened");
+            return true;
+        }
+        false
+    }
+
+    /// Returns `true` if the caller should start the (single) background probe
+    pub(crate) fn start_probe(&self) -> bool {
+        self.probing
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    pub(crate) fn end_probe(&self) {
+        self.probing.store(false, Ordering::Release);
+    }
+}
+
+/// errors that mean the upstream itself is down, not that the request was bad;
+/// a 500 from a failing tool call does not count
+#[must_use]
+pub fn is_outage(error: &PostError) -> bool {
+    match error {
+        PostError::Transport(_) | PostError::Timeout(_) => true,
+        PostError::Status { status, .. } => matches!(
+            *status,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        ),
+        PostError::Other(_) => false,
+    }
+}
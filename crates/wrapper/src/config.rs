@@ -1,22 +1,162 @@
+use crate::listen::listen_arg;
+use crate::rate_limit::rate_arg;
+use crate::streamer_headers::{header_arg, header_name};
+use crate::streamer_mux::split_url_prefix;
 use clap::Parser;
 use reqwest::Url;
 use serde::Deserialize;
 use std::fmt;
+use std::time::Duration;
 
 pub const DEFAULT_LOG_LEVEL: &str = "off";
+pub const LOG_FORMAT_TEXT: &str = "text";
+pub const LOG_FORMAT_JSON: &str = "json";
 pub const DEFAULT_CONCURRENCY: usize = 10;
 pub const DEFAULT_AUTH: Option<&str> = None; // pragma: allowlist secret
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+pub const DEFAULT_RETRY_BASE_MS: u64 = 200;
+pub const DEFAULT_RETRY_MAX_MS: u64 = 2_000;
+pub const DEFAULT_RETRY_STATUS: [u16; 4] = [429, 502, 503, 504];
+pub const DEFAULT_MAX_LINE_BYTES: usize = 16 * 1024 * 1024;
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+pub const DEFAULT_METRICS_INTERVAL: u64 = 60;
+pub const DEFAULT_CIRCUIT_THRESHOLD: u32 = 5;
+pub const DEFAULT_CIRCUIT_PROBE_MS: u64 = 5_000;
+pub const DEFAULT_FAILOVER_AFTER: u32 = 3;
 
-#[derive(Deserialize, Parser)]
+// every on/off flag is a clap switch
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Deserialize, Parser)]
 pub struct Config {
-    /// Gateway MCP endpoint URL
-    #[arg(long = "url", env = "MCP_SERVER_URL")]
-    pub mcp_server_url: String,
+    /// Gateway MCP endpoint URL; repeat as `name=URL` to multiplex several servers
+    #[arg(long = "url", env = "MCP_SERVER_URL", required = true)]
+    pub mcp_server_url: Vec<String>,
+
+    /// Treat several `--url` values as an ordered failover list instead of multiplexing
+    #[arg(long = "failover", default_value_t = false, env = "MCP_FAILOVER")]
+    pub failover: bool,
+
+    /// Consecutive upstream outages before switching to the next `--url`
+    #[arg(
+        long = "failover-after",
+        default_value_t = DEFAULT_FAILOVER_AFTER,
+        env = "MCP_FAILOVER_AFTER"
+    )]
+    pub failover_after: u32,
+
+    /// TOML config file; command line flags and env vars override its values
+    #[arg(long = "config", value_name = "PATH", env = "MCP_WRAPPER_CONFIG")]
+    pub config_file: Option<std::path::PathBuf>,
 
     /// Authorization header value
     #[arg(long = "auth", env = "MCP_AUTH")]
     pub authorization_header: Option<String>,
 
+    /// Bearer token sent on every request (API key when --auth-header-name is set)
+    #[arg(
+        long = "auth-token",
+        env = "MCP_AUTH_TOKEN",
+        conflicts_with = "authorization_header"
+    )]
+    pub auth_token: Option<String>,
+
+    /// Header carrying --auth-token as-is (e.g. X-API-Key) instead of `Authorization: Bearer`
+    #[arg(
+        long = "auth-header-name",
+        env = "MCP_AUTH_HEADER_NAME",
+        requires = "auth_token"
+    )]
+    pub auth_header_name: Option<String>,
+
+    /// `OAuth2` token endpoint for the client-credentials flow
+    #[arg(
+        long = "oauth-token-url",
+        env = "MCP_OAUTH_TOKEN_URL",
+        requires_all = ["oauth_client_id", "oauth_client_secret"],
+        conflicts_with_all = ["authorization_header", "auth_token"]
+    )]
+    pub oauth_token_url: Option<String>,
+
+    /// `OAuth2` client id
+    #[arg(
+        long = "oauth-client-id",
+        env = "MCP_OAUTH_CLIENT_ID",
+        requires = "oauth_token_url"
+    )]
+    pub oauth_client_id: Option<String>,
+
+    /// `OAuth2` client secret
+    #[arg(
+        long = "oauth-client-secret",
+        env = "MCP_OAUTH_CLIENT_SECRET",
+        requires = "oauth_token_url"
+    )]
+    pub oauth_client_secret: Option<String>,
+
+    /// Space-separated `OAuth2` scopes to request
+    #[arg(
+        long = "oauth-scopes",
+        env = "MCP_OAUTH_SCOPES",
+        requires = "oauth_token_url"
+    )]
+    pub oauth_scopes: Option<String>,
+
+    /// Extra header sent with every request as `Name: value` (repeatable, newline-separated in env)
+    #[arg(
+        long = "header",
+        value_name = "NAME: VALUE",
+        env = "MCP_HEADERS",
+        value_delimiter = '\n',
+        value_parser = header_arg
+    )]
+    pub headers: Vec<String>,
+
+    /// Legacy SSE endpoint used when streamable HTTP is not supported (default: derived from --url)
+    #[arg(long = "sse-url", env = "MCP_SSE_URL")]
+    pub sse_url: Option<String>,
+
+    /// Disable automatic fallback to the legacy SSE transport on 404/405
+    #[arg(
+        long = "no-sse-fallback",
+        default_value_t = false,
+        env = "MCP_NO_SSE_FALLBACK"
+    )]
+    pub no_sse_fallback: bool,
+
+    /// Accept clients on `unix:/path.sock` or `pipe:\\.\pipe\name` instead of stdin/stdout
+    #[arg(
+        long = "listen",
+        value_name = "ADDR",
+        env = "MCP_LISTEN",
+        value_parser = listen_arg
+    )]
+    pub listen: Option<String>,
+
+    /// Run `--listen` in the background, restarting the bridge if it dies
+    #[arg(
+        long = "daemon",
+        default_value_t = false,
+        requires = "listen",
+        env = "MCP_DAEMON"
+    )]
+    pub daemon: bool,
+
+    /// Pid file of `--daemon`, also read by `stop` (default: `$XDG_RUNTIME_DIR`)
+    #[arg(long = "pid-file", value_name = "PATH", env = "MCP_PID_FILE")]
+    pub pid_file: Option<std::path::PathBuf>,
+
+    /// Do not open the GET event stream for server-initiated messages
+    #[arg(
+        long = "no-event-stream",
+        default_value_t = false,
+        env = "MCP_NO_EVENT_STREAM"
+    )]
+    pub no_event_stream: bool,
+
+    /// Send a ping after this many seconds without upstream traffic (0 disables)
+    #[arg(long = "keepalive", default_value_t = 0, env = "MCP_KEEPALIVE")]
+    pub keepalive: u64,
+
     /// Max concurrent tool calls
     #[arg(long, default_value_t = DEFAULT_CONCURRENCY, env = "CONCURRENCY")]
     pub concurrency: usize,
@@ -31,14 +171,184 @@ pub struct Config {
     #[arg(short, long = "log-file", env = "MCP_LOG_FILE")]
     pub mcp_wrapper_log_file: Option<String>,
 
+    /// Log output format: text or json (one object per line)
+    #[arg(
+        long = "log-format",
+        default_value_t = String::from(LOG_FORMAT_TEXT),
+        value_parser = [LOG_FORMAT_TEXT, LOG_FORMAT_JSON],
+        env = "MCP_LOG_FORMAT"
+    )]
+    pub log_format: String,
+
+    /// Extra field or query parameter name masked in logs and error messages (repeatable)
+    #[arg(
+        long = "redact-field",
+        value_name = "NAME",
+        env = "MCP_REDACT_FIELDS",
+        value_delimiter = ','
+    )]
+    pub redact_fields: Vec<String>,
+
     /// Response timeout in seconds
     #[arg(long = "timeout", default_value_t = 60, env = "MCP_TOOL_CALL_TIMEOUT")]
     pub mcp_tool_call_timeout: u64,
 
-    /// Path to a custom CA certificate file (PEM format, e.g., .pem, .crt, .cert)
-    #[arg(long = "tls-cert", value_name = "PATH", env = "TLS_CERT")]
+    /// Seconds a single upstream exchange may take, including the streamed response
+    #[arg(long = "request-timeout", env = "MCP_REQUEST_TIMEOUT")]
+    pub request_timeout: Option<u64>,
+
+    /// Seconds allowed for establishing the upstream connection
+    #[arg(long = "connect-timeout", env = "MCP_CONNECT_TIMEOUT")]
+    pub connect_timeout: Option<u64>,
+
+    /// Retries after a failed upstream request (0 disables retries)
+    #[arg(
+        long = "max-retries",
+        default_value_t = DEFAULT_MAX_RETRIES,
+        env = "MCP_MAX_RETRIES"
+    )]
+    pub max_retries: u32,
+
+    /// Initial retry backoff in milliseconds, doubled on every attempt
+    #[arg(
+        long = "retry-base-ms",
+        default_value_t = DEFAULT_RETRY_BASE_MS,
+        env = "MCP_RETRY_BASE_MS"
+    )]
+    pub retry_base_ms: u64,
+
+    /// Upper bound of a single retry backoff in milliseconds
+    #[arg(
+        long = "retry-max-ms",
+        default_value_t = DEFAULT_RETRY_MAX_MS,
+        env = "MCP_RETRY_MAX_MS"
+    )]
+    pub retry_max_ms: u64,
+
+    /// HTTP status codes that are retried (comma-separated)
+    #[arg(
+        long = "retry-status",
+        value_delimiter = ',',
+        default_values_t = DEFAULT_RETRY_STATUS,
+        env = "MCP_RETRY_STATUS"
+    )]
+    pub retry_status: Vec<u16>,
+
+    /// Also retry non-idempotent methods such as tools/call, which may run them twice
+    #[arg(
+        long = "retry-non-idempotent",
+        default_value_t = false,
+        env = "MCP_RETRY_NON_IDEMPOTENT"
+    )]
+    pub retry_non_idempotent: bool,
+
+    /// File the negotiated session is saved to and resumed from on restart
+    #[arg(long = "session-file", value_name = "PATH", env = "MCP_SESSION_FILE")]
+    pub session_file: Option<std::path::PathBuf>,
+
+    /// Seconds to answer repeated list requests (tools, prompts, resources) locally (0 disables)
+    #[arg(
+        long = "list-cache-ttl",
+        default_value_t = 0,
+        env = "MCP_LIST_CACHE_TTL"
+    )]
+    pub list_cache_ttl: u64,
+
+    /// Forward stdin lines without checking that they are JSON-RPC 2.0
+    #[arg(long = "no-validate", default_value_t = false, env = "MCP_NO_VALIDATE")]
+    pub no_validate: bool,
+
+    /// Longest accepted stdin line in bytes (0 = unlimited)
+    #[arg(
+        long = "max-line-bytes",
+        default_value_t = DEFAULT_MAX_LINE_BYTES,
+        env = "MCP_MAX_LINE_BYTES"
+    )]
+    pub max_line_bytes: usize,
+
+    /// Largest accepted upstream response in bytes (0 = unlimited)
+    #[arg(
+        long = "max-response-bytes",
+        default_value_t = DEFAULT_MAX_RESPONSE_BYTES,
+        env = "MCP_MAX_RESPONSE_BYTES"
+    )]
+    pub max_response_bytes: usize,
+
+    /// Maximum request rate towards the upstream, e.g. `10/s` or `600/m`
+    #[arg(
+        long = "rate-limit",
+        value_name = "N/s",
+        env = "MCP_RATE_LIMIT",
+        value_parser = rate_arg
+    )]
+    pub rate_limit: Option<String>,
+
+    /// Write responses in the order requests were read (slower with slow requests)
+    #[arg(long = "ordered", default_value_t = false, env = "MCP_ORDERED")]
+    pub ordered: bool,
+
+    /// Drop requests that repeat an identical in-flight request (same id and payload)
+    #[arg(
+        long = "dedup-requests",
+        default_value_t = false,
+        env = "MCP_DEDUP_REQUESTS"
+    )]
+    pub dedup_requests: bool,
+
+    /// Consecutive upstream failures before requests fail fast (0 disables)
+    #[arg(
+        long = "circuit-threshold",
+        default_value_t = DEFAULT_CIRCUIT_THRESHOLD,
+        env = "MCP_CIRCUIT_THRESHOLD"
+    )]
+    pub circuit_threshold: u32,
+
+    /// Interval in milliseconds between upstream probes while the circuit is open
+    #[arg(
+        long = "circuit-probe-ms",
+        default_value_t = DEFAULT_CIRCUIT_PROBE_MS,
+        env = "MCP_CIRCUIT_PROBE_MS"
+    )]
+    pub circuit_probe_ms: u64,
+
+    /// File receiving one JSON metrics snapshot per interval
+    #[arg(long = "metrics-file", value_name = "PATH", env = "MCP_METRICS_FILE")]
+    pub metrics_file: Option<std::path::PathBuf>,
+
+    /// Seconds between metrics snapshots
+    #[arg(
+        long = "metrics-interval",
+        default_value_t = DEFAULT_METRICS_INTERVAL,
+        env = "MCP_METRICS_INTERVAL"
+    )]
+    pub metrics_interval: u64,
+
+    /// File receiving every request and its responses as JSON lines, for `replay`
+    #[arg(long = "record", value_name = "PATH", env = "MCP_RECORD")]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Path to a custom CA certificate bundle (PEM format, e.g., .pem, .crt, .cert)
+    #[arg(
+        long = "tls-cert",
+        visible_alias = "ca-cert",
+        value_name = "PATH",
+        env = "TLS_CERT"
+    )]
     pub tls_cert: Option<std::path::PathBuf>,
 
+    /// Client certificate for mutual TLS (PEM, may also contain the key)
+    #[arg(long = "client-cert", value_name = "PATH", env = "MCP_CLIENT_CERT")]
+    pub client_cert: Option<std::path::PathBuf>,
+
+    /// Private key for --client-cert (PEM)
+    #[arg(
+        long = "client-key",
+        value_name = "PATH",
+        env = "MCP_CLIENT_KEY",
+        requires = "client_cert"
+    )]
+    pub client_key: Option<std::path::PathBuf>,
+
     /// Content type header to send to server
     #[arg(
         long,
@@ -56,6 +366,14 @@ pub struct Config {
     )]
     pub http_pool_per_worker: bool,
 
+    /// Send every request of an established session over one HTTP connection
+    #[arg(
+        long = "session-affinity",
+        default_value_t = false,
+        env = "MCP_SESSION_AFFINITY"
+    )]
+    pub session_affinity: bool,
+
     /// Maximum idle connections per host in the HTTP pool
     #[arg(long = "http-pool-size", env = "HTTP_POOL_SIZE")]
     pub http_pool_size: Option<usize>,
@@ -69,7 +387,12 @@ pub struct Config {
     pub http_pool_idle_timeout: Option<u64>,
 
     /// Disable TLS certificate verification (insecure, use only for testing)
-    #[arg(long = "insecure", default_value_t = false, env = "INSECURE")]
+    #[arg(
+        long = "insecure",
+        visible_alias = "insecure-skip-verify",
+        default_value_t = false,
+        env = "INSECURE"
+    )]
     pub insecure: bool,
 }
 
@@ -78,19 +401,81 @@ impl fmt::Debug for Config {
         f.debug_struct("Config")
             .field(
                 "mcp_server_url",
-                &sanitize_url_for_debug(&self.mcp_server_url),
+                &self
+                    .mcp_server_url
+                    .iter()
+                    .map(|raw| sanitize_upstream_for_debug(raw))
+                    .collect::<Vec<_>>(),
             )
+            .field("failover", &self.failover)
+            .field("failover_after", &self.failover_after)
+            .field("config_file", &self.config_file)
             .field(
                 "authorization_header",
                 &self.authorization_header.as_ref().map(|_| "<redacted>"),
             )
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("auth_header_name", &self.auth_header_name)
+            .field("oauth_token_url", &self.oauth_token_url)
+            .field("oauth_client_id", &self.oauth_client_id)
+            .field(
+                "oauth_client_secret",
+                &self.oauth_client_secret.as_ref().map(|_| "<redacted>"),
+            )
+            .field("oauth_scopes", &self.oauth_scopes)
+            .field(
+                "headers",
+                &self
+                    .headers
+                    .iter()
+                    .map(|h| header_name(h))
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "sse_url",
+                &self.sse_url.as_deref().map(sanitize_url_for_debug),
+            )
+            .field("no_sse_fallback", &self.no_sse_fallback)
+            .field("listen", &self.listen)
+            .field("daemon", &self.daemon)
+            .field("pid_file", &self.pid_file)
+            .field("no_event_stream", &self.no_event_stream)
+            .field("keepalive", &self.keepalive)
             .field("concurrency", &self.concurrency)
             .field("mcp_wrapper_log_level", &self.mcp_wrapper_log_level)
             .field("mcp_wrapper_log_file", &self.mcp_wrapper_log_file)
+            .field("log_format", &self.log_format)
+            .field("redact_fields", &self.redact_fields)
             .field("mcp_tool_call_timeout", &self.mcp_tool_call_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("max_retries", &self.max_retries)
+            .field("retry_base_ms", &self.retry_base_ms)
+            .field("retry_max_ms", &self.retry_max_ms)
+            .field("retry_status", &self.retry_status)
+            .field("retry_non_idempotent", &self.retry_non_idempotent)
+            .field("session_file", &self.session_file)
+            .field("list_cache_ttl", &self.list_cache_ttl)
+            .field("no_validate", &self.no_validate)
+            .field("max_line_bytes", &self.max_line_bytes)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("rate_limit", &self.rate_limit)
+            .field("ordered", &self.ordered)
+            .field("dedup_requests", &self.dedup_requests)
+            .field("circuit_threshold", &self.circuit_threshold)
+            .field("circuit_probe_ms", &self.circuit_probe_ms)
+            .field("metrics_file", &self.metrics_file)
+            .field("metrics_interval", &self.metrics_interval)
+            .field("record", &self.record)
             .field("tls_cert", &self.tls_cert)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
             .field("mcp_content_type", &self.mcp_content_type)
             .field("http_pool_per_worker", &self.http_pool_per_worker)
+            .field("session_affinity", &self.session_affinity)
             .field("http_pool_size", &self.http_pool_size)
             .field("http2", &self.http2)
             .field("http_pool_idle_timeout", &self.http_pool_idle_timeout)
@@ -99,7 +484,26 @@ impl fmt::Debug for Config {
     }
 }
 
-fn sanitize_url_for_debug(raw: &str) -> String {
+impl Config {
+    /// limit for one HTTP request; `--request-timeout` bounds the whole
+    /// exchange, so a longer one raises `--timeout` instead of being cut by it
+    #[must_use]
+    pub fn response_timeout(&self) -> Duration {
+        let secs = self
+            .mcp_tool_call_timeout
+            .max(self.request_timeout.unwrap_or(0));
+        Duration::from_secs(secs)
+    }
+}
+
+fn sanitize_upstream_for_debug(raw: &str) -> String {
+    match split_url_prefix(raw) {
+        (Some(prefix), url) => format!("{prefix}={}", sanitize_url_for_debug(url)),
+        (None, url) => sanitize_url_for_debug(url),
+    }
+}
+
+pub(crate) fn sanitize_url_for_debug(raw: &str) -> String {
     let Ok(mut url) = Url::parse(raw) else {
         return raw.to_string();
     };
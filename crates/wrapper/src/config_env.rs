@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::streamer_headers::header_arg;
+
+/// long flags expanded once after parsing, whatever their source
+pub(crate) const EXPANDED_FLAGS: [&str; 2] = ["url", "header"];
+
+/// expands `${VAR}` and `${VAR:-default}` references, `$$` is a literal `$`
+/// # Errors
+/// * referenced variable is not set and has no default
+/// * unterminated `${`
+pub fn expand_env(raw: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos + 1..];
+        if let Some(escaped) = tail.strip_prefix('$') {
+            out.push('$');
+            rest = escaped;
+        } else if let Some(body) = tail.strip_prefix('{') {
+            let end = body
+                .find('}')
+                .ok_or_else(|| "Unterminated '${' in value".to_string())?;
+            out.push_str(&lookup(&body[..end])?);
+            rest = &body[end + 1..];
+        } else {
+            out.push('$');
+            rest = tail;
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn lookup(reference: &str) -> Result<String, String> {
+    let (name, default) = match reference.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (reference, None),
+    };
+    match (std::env::var(name), default) {
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(default)) => Ok(default.to_string()),
+        (Err(_), None) => Err(format!("Environment variable '{name}' is not set")),
+    }
+}
+
+impl Config {
+    /// expands `${VAR}` references in `--url` and `--header` values
+    /// # Errors
+    /// * referenced variable is not set and has no default
+    /// * header no longer valid after expansion
+    pub fn resolve_env(&mut self) -> Result<(), String> {
+        for url in &mut self.mcp_server_url {
+            *url = expand_env(url).map_err(|e| format!("--url: {e}"))?;
+        }
+        for header in &mut self.headers {
+            *header = expand_env(header)
+                .and_then(|value| header_arg(&value))
+                .map_err(|e| format!("--header: {e}"))?;
+        }
+        Ok(())
+    }
+}
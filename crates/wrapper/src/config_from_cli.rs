@@ -1,16 +1,45 @@
 use crate::config::Config;
-use clap::Parser;
+use crate::config_from_file::{config_path, file_args};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 use std::ffi::OsString;
+use std::path::Path;
 
 /// implements config init from cli arguments
 impl Config {
-    /// loads config from cli arguments
+    /// loads config from cli arguments, merged over the `--config` file if any;
+    /// `${VAR}` in `--url` and `--header` values is expanded from the environment;
+    /// prints the error and exits on invalid arguments
     #[must_use]
     pub fn from_cli<I, T>(args: I) -> Self
     where
         I: IntoIterator<Item = T>,
         T: Into<OsString> + Clone,
     {
-        Config::parse_from(args)
+        Self::try_from_cli(args).unwrap_or_else(|e| e.exit())
+    }
+
+    /// same as [`Config::from_cli`] but returns the error instead of exiting
+    /// # Errors
+    /// * invalid arguments, unreadable config file or unresolved `${VAR}`
+    pub fn try_from_cli<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        if let Some(path) = config_path(&args) {
+            let file = file_args(Path::new(&path), &args)
+                .map_err(|e| Config::command().error(ErrorKind::Io, e))?;
+            // file values go first so that explicit flags win
+            let cli = args.split_off(args.len().min(1));
+            args.extend(file);
+            args.extend(cli);
+        }
+        let mut config = Config::try_parse_from(args)?;
+        config
+            .resolve_env()
+            .map_err(|e| Config::command().error(ErrorKind::ValueValidation, e))?;
+        Ok(config)
     }
 }
@@ -0,0 +1,142 @@
+use crate::config::Config;
+use crate::config_env::{EXPANDED_FLAGS, expand_env};
+use clap::{Arg, Command, CommandFactory};
+use std::ffi::OsString;
+use std::path::Path;
+use toml::{Table, Value};
+
+const CONFIG_LONG: &str = "config";
+const CONFIG_ENV: &str = "MCP_WRAPPER_CONFIG";
+
+/// finds `--config PATH` / `--config=PATH` in raw args, falling back to the env var
+pub(crate) fn config_path(args: &[OsString]) -> Option<OsString> {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let Some(arg) = arg.to_str() else { continue };
+        if arg == "--config" {
+            return iter.next().cloned();
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.into());
+        }
+    }
+    std::env::var_os(CONFIG_ENV)
+}
+
+/// Converts the config file into CLI arguments.
+/// Keys are long flag names (`_` or `-`); tables only group keys.
+/// Keys already given on the command line or via their env var are skipped.
+/// # Errors
+/// * unreadable or invalid file, unknown key, unset `${VAR}`
+pub fn file_args(path: &Path, cli: &[OsString]) -> Result<Vec<OsString>, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config file {}: {e}", path.display()))?;
+    let table: Table =
+        toml::from_str(&raw).map_err(|e| format!("Invalid config file {}: {e}", path.display()))?;
+
+    let command = Config::command();
+    let mut args = Vec::new();
+    push_table(&command, &table, cli, &mut args)?;
+    Ok(args)
+}
+
+fn push_table(
+    command: &Command,
+    table: &Table,
+    cli: &[OsString],
+    args: &mut Vec<OsString>,
+) -> Result<(), String> {
+    for (key, value) in table {
+        if let Value::Table(section) = value {
+            push_table(command, section, cli, args)?;
+            continue;
+        }
+        let long = key.replace('_', "-");
+        if long == CONFIG_LONG {
+            continue;
+        }
+        let arg = command
+            .get_arguments()
+            .find(|a| {
+                a.get_long() == Some(long.as_str())
+                    || a.get_all_aliases()
+                        .is_some_and(|aliases| aliases.contains(&long.as_str()))
+            })
+            .ok_or_else(|| format!("Unknown config key '{key}'"))?;
+        if given_on_cli(arg, cli)
+            || arg
+                .get_env()
+                .is_some_and(|env| std::env::var_os(env).is_some())
+        {
+            continue;
+        }
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    push_value(arg, &long, item, args)?;
+                }
+            }
+            _ => push_value(arg, &long, value, args)?,
+        }
+    }
+    Ok(())
+}
+
+fn push_value(
+    arg: &Arg,
+    long: &str,
+    value: &Value,
+    args: &mut Vec<OsString>,
+) -> Result<(), String> {
+    let text = match value {
+        // expanded after parsing, together with command line and env values
+        Value::String(s) if EXPANDED_FLAGS.contains(&long) => s.clone(),
+        Value::String(s) => expand_env(s).map_err(|e| format!("Config key '{long}': {e}"))?,
+        // flags take no value: `true` sets them, `false` leaves the default
+        Value::Boolean(b) if !arg.get_action().takes_values() => {
+            if *b {
+                args.push(format!("--{long}").into());
+            }
+            return Ok(());
+        }
+        Value::Boolean(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Datetime(d) => d.to_string(),
+        Value::Array(_) | Value::Table(_) => {
+            return Err(format!(
+                "Config key '{long}': nested values are not supported"
+            ));
+        }
+    };
+    args.push(format!("--{long}={text}").into());
+    Ok(())
+}
+
+/// `true` if `arg` appears in `cli` under its long name, an alias or a short flag
+fn given_on_cli(arg: &Arg, cli: &[OsString]) -> bool {
+    let longs: Vec<String> = arg
+        .get_long()
+        .into_iter()
+        .chain(arg.get_all_aliases().unwrap_or_default())
+        .map(|l| format!("--{l}"))
+        .collect();
+    let shorts: Vec<String> = arg
+        .get_short()
+        .into_iter()
+        .chain(arg.get_all_short_aliases().unwrap_or_default())
+        .map(|s| format!("-{s}"))
+        .collect();
+    let takes_value = arg.get_action().takes_values();
+    cli.iter().skip(1).filter_map(|a| a.to_str()).any(|a| {
+        longs.iter().any(|l| {
+            a == l
+                || a.strip_prefix(l.as_str())
+                    .is_some_and(|v| v.starts_with('='))
+        }) || (!a.starts_with("--")
+            && shorts.iter().any(|s| {
+                // `-c` alone, or `-cVALUE` / `-c=VALUE` for flags taking a value
+                a == s || (takes_value && a.starts_with(s.as_str()))
+            }))
+    })
+}
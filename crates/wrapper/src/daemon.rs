@@ -0,0 +1,376 @@
+use crate::config::Config;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// argv[1] that stops a running daemon
+pub const STOP_COMMAND: &str = "stop";
+
+/// set on the processes a daemon starts, tells them their role
+const ROLE_ENV: &str = "MCP_DAEMON_ROLE";
+const ROLE_SUPERVISOR: &str = "supervisor";
+const ROLE_BRIDGE: &str = "bridge";
+const PID_FILE_NAME: &str = "mcp_stdio_wrapper.pid";
+/// per-user directory the default pid file goes to
+const RUNTIME_DIR_ENV: &str = "XDG_RUNTIME_DIR";
+
+/// which process of a `--daemon` start this is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// started by the user, detaches and exits
+    Launcher,
+    /// owns the pid file and restarts the bridge
+    Supervisor,
+    /// serves `--listen` connections
+    Bridge,
+}
+
+/// role of the current process, from the environment
+#[must_use]
+pub fn current_role() -> Role {
+    match std::env::var(ROLE_ENV).as_deref() {
+        Ok(ROLE_SUPERVISOR) => Role::Supervisor,
+        Ok(ROLE_BRIDGE) => Role::Bridge,
+        _ => Role::Launcher,
+    }
+}
+
+/// `--pid-file`, or a file in `$XDG_RUNTIME_DIR`; a shared temp dir
+/// would let other users plant or replace the file
+/// # Errors
+/// * no `--pid-file` and no runtime dir
+pub fn pid_path(pid_file: Option<&Path>) -> io::Result<PathBuf> {
+    if let Some(path) = pid_file {
+        return Ok(path.to_path_buf());
+    }
+    std::env::var_os(RUNTIME_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(PID_FILE_NAME))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{RUNTIME_DIR_ENV} is not set, pass --pid-file"),
+            )
+        })
+}
+
+/// pid file given to `stop` as `--pid-file PATH`, `--pid-file=PATH` or `MCP_PID_FILE`
+/// # Errors
+/// * see [`pid_path`]
+pub fn stop_pid_path(args: &[OsString]) -> io::Result<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--pid-file" {
+            return pid_path(iter.next().map(Path::new));
+        }
+        if let Some(path) = arg.to_str().and_then(|a| a.strip_prefix("--pid-file=")) {
+            return pid_path(Some(Path::new(path)));
+        }
+    }
+    pid_path(std::env::var_os("MCP_PID_FILE").as_deref().map(Path::new))
+}
+
+/// pid stored in `path`
+/// # Errors
+/// * file missing or not a pid
+pub fn read_pid(path: &Path) -> io::Result<u32> {
+    std::fs::read_to_string(path)?.trim().parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {e}", path.display()),
+        )
+    })
+}
+
+/// writes the pid to a new file, replacing only the file of a daemon that
+/// is gone; never follows a symlink at `path`
+/// # Errors
+/// * another daemon owns `path`, or the file can not be written
+pub fn write_pid_file(path: &Path, pid: u32) -> io::Result<()> {
+    let mut file = match create_pid_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            if let Ok(running) = read_pid(path)
+                && running != pid
+                && is_running(running)
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "daemon already running with pid {running} ({})",
+                        path.display()
+                    ),
+                ));
+            }
+            std::fs::remove_file(path)?;
+            create_pid_file(path)?
+        }
+        created => created?,
+    };
+    writeln!(file, "{pid}")
+}
+
+fn create_pid_file(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o644).custom_flags(libc::O_NOFOLLOW);
+    }
+    options.open(path)
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::{
+        Config, ROLE_BRIDGE, ROLE_ENV, ROLE_SUPERVISOR, pid_path, read_pid, write_pid_file,
+    };
+    use crate::retry::RetryPolicy;
+    use std::io::{self, BufRead, Write};
+    use std::os::unix::process::CommandExt;
+    use std::path::Path;
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+    use tokio::signal::unix::{SignalKind, signal};
+    use tracing::{error, info, warn};
+
+    /// a bridge that ran this long is healthy again, the backoff starts over
+    const HEALTHY_AFTER: Duration = Duration::from_secs(60);
+    /// how long `stop` and the supervisor wait for a clean exit
+    const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+    /// line the supervisor sends the launcher once it owns the pid file
+    const READY: &str = "ready";
+
+    /// jittered backoff between bridge restarts, 1s doubling up to a minute
+    fn restart_backoff() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: u32::MAX,
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            statuses: Vec::new(),
+            idempotent_only: false,
+        }
+    }
+
+    /// this binary with the same arguments, in `role`, detached from stdio
+    fn respawn(role: &str) -> io::Result<std::process::Command> {
+        let mut command = std::process::Command::new(std::env::current_exe()?);
+        command
+            .args(std::env::args_os().skip(1))
+            .env(ROLE_ENV, role)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        Ok(command)
+    }
+
+    fn send(pid: u32, signal: libc::c_int) -> io::Result<()> {
+        // 0 and negative pids address process groups
+        let pid = libc::pid_t::try_from(pid)
+            .ok()
+            .filter(|pid| *pid > 0)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("pid {pid}")))?;
+        // SAFETY: kill has no memory effects, an unknown pid only returns an error
+        if unsafe { libc::kill(pid, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    /// a process we may not signal still runs
+    pub(super) fn is_running(pid: u32) -> bool {
+        match send(pid, 0) {
+            Ok(()) => true,
+            Err(e) => e.raw_os_error() == Some(libc::EPERM),
+        }
+    }
+
+    /// starts the supervisor in its own process group and returns its pid
+    /// once it owns the pid file
+    pub(super) fn detach() -> io::Result<u32> {
+        let mut child = respawn(ROLE_SUPERVISOR)?
+            .stdout(Stdio::piped())
+            .process_group(0)
+            .spawn()?;
+        let mut line = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            io::BufReader::new(stdout).read_line(&mut line)?;
+        }
+        let line = line.trim();
+        if line == READY {
+            return Ok(child.id());
+        }
+        let status = child.wait()?;
+        Err(io::Error::other(if line.is_empty() {
+            format!("supervisor exited with {status}")
+        } else {
+            line.to_string()
+        }))
+    }
+
+    /// tells the launcher whether the supervisor is up
+    fn confirm(error: Option<&io::Error>) {
+        let mut stdout = io::stdout();
+        let _ = match error {
+            None => writeln!(stdout, "{READY}"),
+            Some(e) => writeln!(stdout, "{e}"),
+        };
+        let _ = stdout.flush();
+    }
+
+    /// keeps a bridge process running until SIGTERM; SIGHUP is passed on for reloads
+    pub(super) async fn supervise(config: &Config) -> io::Result<()> {
+        let path = match pid_path(config.pid_file.as_deref())
+            .and_then(|path| write_pid_file(&path, std::process::id()).map(|()| path))
+        {
+            Ok(path) => path,
+            Err(e) => {
+                confirm(Some(&e));
+                return Err(e);
+            }
+        };
+        confirm(None);
+        let result = supervise_bridge().await;
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    async fn supervise_bridge() -> io::Result<()> {
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut hangup = signal(SignalKind::hangup())?;
+        let backoff = restart_backoff();
+        let mut restarts = 0;
+        loop {
+            let started = Instant::now();
+            let mut bridge = tokio::process::Command::from(respawn(ROLE_BRIDGE)?).spawn()?;
+            let pid = bridge.id().unwrap_or_default();
+            info!("Bridge started with pid {pid}");
+            let status = loop {
+                tokio::select! {
+                    status = bridge.wait() => break status?,
+                    _ = hangup.recv() => {
+                        let _ = send(pid, libc::SIGHUP);
+                    }
+                    _ = terminate.recv() => return stop_bridge(&mut bridge, pid).await,
+                    _ = interrupt.recv() => return stop_bridge(&mut bridge, pid).await,
+                }
+            };
+            if status.success() {
+                info!("Bridge finished, supervisor exiting");
+                return Ok(());
+            }
+            if started.elapsed() >= HEALTHY_AFTER {
+                restarts = 0;
+            }
+            let delay = backoff.delay(restarts);
+            restarts = restarts.saturating_add(1);
+            error!("Bridge exited with {status}, restarting in {delay:?}");
+            tokio::select! {
+                () = tokio::time::sleep(delay) => {}
+                _ = terminate.recv() => return Ok(()),
+                _ = interrupt.recv() => return Ok(()),
+            }
+        }
+    }
+
+    async fn stop_bridge(bridge: &mut tokio::process::Child, pid: u32) -> io::Result<()> {
+        let _ = send(pid, libc::SIGTERM);
+        if tokio::time::timeout(STOP_TIMEOUT, bridge.wait())
+            .await
+            .is_err()
+        {
+            warn!("Bridge {pid} ignored SIGTERM, killing it");
+            bridge.kill().await?;
+        }
+        Ok(())
+    }
+
+    /// sends SIGTERM to the daemon in `path` and waits for it to exit
+    pub(super) async fn stop(path: &Path) -> io::Result<u32> {
+        let pid = read_pid(path)?;
+        match send(pid, libc::SIGTERM) {
+            Ok(()) => {}
+            // only a missing process makes the file stale
+            Err(e) if e.raw_os_error() == Some(libc::ESRCH) => {
+                let _ = std::fs::remove_file(path);
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no daemon with pid {pid}, removed stale {}", path.display()),
+                ));
+            }
+            Err(e) => {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("can not signal daemon {pid}: {e}"),
+                ));
+            }
+        }
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while is_running(pid) {
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("daemon {pid} did not exit"),
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        Ok(pid)
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use super::Config;
+    use std::io;
+    use std::path::Path;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "--daemon is only supported on unix",
+        )
+    }
+
+    pub(super) fn is_running(_pid: u32) -> bool {
+        false
+    }
+
+    pub(super) fn detach() -> io::Result<u32> {
+        Err(unsupported())
+    }
+
+    pub(super) async fn supervise(_config: &Config) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub(super) async fn stop(_path: &Path) -> io::Result<u32> {
+        Err(unsupported())
+    }
+}
+
+use imp::is_running;
+
+/// starts the supervisor in the background and returns its pid
+/// # Errors
+/// * the process can not be started, or the platform has no daemon support
+pub fn detach() -> io::Result<u32> {
+    imp::detach()
+}
+
+/// writes the pid file and restarts the bridge with jittered backoff until stopped
+/// # Errors
+/// * pid file owned by a running daemon, or the bridge can not be started
+pub async fn supervise(config: &Config) -> io::Result<()> {
+    imp::supervise(config).await
+}
+
+/// stops the daemon whose pid is in `path`, returns its pid
+/// # Errors
+/// * no pid file, no such process, or it did not exit in time
+pub async fn stop(path: &Path) -> io::Result<u32> {
+    imp::stop(path).await
+}
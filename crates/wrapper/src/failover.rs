@@ -0,0 +1,59 @@
+use bytes::Bytes;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// active upstream of an ordered `--failover` url list
+#[derive(Debug)]
+pub struct Failover {
+    threshold: u32,
+    active: AtomicUsize,
+    failures: AtomicU32,
+    /// client initialize, replayed against the next upstream
+    initialize: Mutex<Option<Bytes>>,
+    /// one switch at a time
+    pub(crate) switching: tokio::sync::Mutex<()>,
+}
+
+impl Failover {
+    /// switches after `threshold` consecutive outages (at least one)
+    #[must_use]
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            active: AtomicUsize::new(0),
+            failures: AtomicU32::new(0),
+            initialize: Mutex::new(None),
+            switching: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// index of the upstream requests go to
+    #[must_use]
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_active(&self, index: usize) {
+        self.failures.store(0, Ordering::Relaxed);
+        self.active.store(index, Ordering::Release);
+    }
+
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if this outage should move traffic to the next upstream
+    pub fn record_failure(&self) -> bool {
+        self.failures.fetch_add(1, Ordering::Relaxed) + 1 >= self.threshold
+    }
+
+    pub(crate) fn remember_initialize(&self, payload: &Bytes) {
+        if let Ok(mut initialize) = self.initialize.lock() {
+            *initialize = Some(payload.clone());
+        }
+    }
+
+    pub(crate) fn initialize(&self) -> Option<Bytes> {
+        self.initialize.lock().ok().and_then(|guard| guard.clone())
+    }
+}
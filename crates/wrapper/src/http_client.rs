@@ -1,5 +1,7 @@
 use crate::config::Config;
-use crate::streamer_error::{build_error, invalid_error, read_error};
+use crate::streamer_error::{
+    build_error, empty_bundle_error, identity_error, invalid_error, read_error,
+};
 use reqwest::Client;
 use tokio::fs::read;
 
@@ -9,9 +11,13 @@ use std::time::Duration;
 /// * wrong parameters, invalid certs
 pub async fn get_http_client(config: &Config) -> Result<Client, String> {
     let mut build = Client::builder()
-        .timeout(Duration::from_secs(config.mcp_tool_call_timeout))
+        .timeout(config.response_timeout())
         .tcp_nodelay(true);
 
+    if let Some(connect_timeout) = config.connect_timeout {
+        build = build.connect_timeout(Duration::from_secs(connect_timeout));
+    }
+
     if config.http2 {
         build = build.http2_prior_knowledge();
     }
@@ -32,9 +38,28 @@ pub async fn get_http_client(config: &Config) -> Result<Client, String> {
         let cert_bytes = read(cert_path)
             .await
             .map_err(|e| read_error(cert_path, &e))?;
-        let cert = reqwest::Certificate::from_pem(&cert_bytes)
+        let certs = reqwest::Certificate::from_pem_bundle(&cert_bytes)
             .map_err(|e| invalid_error(cert_path, &e))?;
-        build = build.add_root_certificate(cert);
+        if certs.is_empty() {
+            return Err(empty_bundle_error(cert_path));
+        }
+        for cert in certs {
+            build = build.add_root_certificate(cert);
+        }
+    }
+
+    if let Some(cert_path) = &config.client_cert {
+        let mut pem = read(cert_path)
+            .await
+            .map_err(|e| read_error(cert_path, &e))?;
+        if let Some(key_path) = &config.client_key {
+            let key = read(key_path).await.map_err(|e| read_error(key_path, &e))?;
+            pem.push(b'\n');
+            pem.extend_from_slice(&key);
+        }
+        let identity =
+            reqwest::Identity::from_pem(&pem).map_err(|e| identity_error(cert_path, &e))?;
+        build = build.identity(identity);
     }
 
     build.build().map_err(|e| build_error(&e))
@@ -0,0 +1,74 @@
+use crate::json_rpc_id_fast::parse_id_fast;
+use jsonrpc_core::Id;
+use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// request ids currently being processed by the workers
+#[derive(Debug, Default)]
+pub struct InFlight {
+    requests: Mutex<HashMap<Id, u64>>,
+}
+
+/// outcome of [`InFlight::begin`]
+#[derive(Debug)]
+pub enum Admission<'a> {
+    /// notification or id reused with a different payload
+    Untracked,
+    /// tracked until the guard is dropped
+    Tracked(InFlightGuard<'a>),
+    /// identical request already in flight
+    Duplicate(Id),
+}
+
+/// removes the id from [`InFlight`] when the response is written
+#[derive(Debug)]
+pub struct InFlightGuard<'a> {
+    inflight: &'a InFlight,
+    id: Id,
+}
+
+impl InFlight {
+    /// registers a request before it is sent upstream
+    pub fn begin(&self, payload: &[u8]) -> Admission<'_> {
+        let id = parse_id_fast(payload);
+        if id == Id::Null {
+            return Admission::Untracked;
+        }
+        let mut hasher = DefaultHasher::new();
+        payload.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let Ok(mut requests) = self.requests.lock() else {
+            return Admission::Untracked;
+        };
+        match requests.entry(id.clone()) {
+            Entry::Occupied(e) if *e.get() == hash => Admission::Duplicate(id),
+            Entry::Occupied(_) => Admission::Untracked,
+            Entry::Vacant(e) => {
+                e.insert(hash);
+                Admission::Tracked(InFlightGuard { inflight: self, id })
+            }
+        }
+    }
+
+    /// number of tracked requests
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.requests.lock().map(|r| r.len()).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut requests) = self.inflight.requests.lock() {
+            requests.remove(&self.id);
+        }
+    }
+}
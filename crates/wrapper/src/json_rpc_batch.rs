@@ -0,0 +1,51 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use serde_json::Value;
+
+/// a stdin line holding a JSON array, i.e. a JSON-RPC batch
+#[must_use]
+pub fn is_batch(line: &[u8]) -> bool {
+    line.trim_ascii_start().first() == Some(&b'[')
+}
+
+/// splits a batch into one message per entry
+/// # Errors
+/// * the line is not a JSON array
+pub fn split_batch(line: &[u8]) -> Result<Vec<Bytes>, serde_json::Error> {
+    let entries: Vec<Value> = serde_json::from_slice(line)?;
+    Ok(entries
+        .iter()
+        .map(|entry| Bytes::from(entry.to_string()))
+        .collect())
+}
+
+/// a message carrying `method` is a request or notification, anything else a response
+#[must_use]
+pub fn is_response(message: &[u8]) -> bool {
+    serde_json::from_slice::<Value>(message)
+        .is_ok_and(|value| value.is_object() && value.get("method").is_none())
+}
+
+/// a batch entry that expects an answer, i.e. not a notification
+#[must_use]
+pub fn expects_response(entry: &[u8]) -> bool {
+    serde_json::from_slice::<Value>(entry).is_ok_and(|value| value.get("id").is_some())
+}
+
+/// joins responses into one batch response, `None` when all entries were notifications
+#[must_use]
+pub fn join_batch(responses: &[Bytes]) -> Option<Bytes> {
+    if responses.is_empty() {
+        return None;
+    }
+    let len = responses.iter().map(Bytes::len).sum::<usize>() + responses.len() + 1;
+    let mut out = BytesMut::with_capacity(len);
+    out.put_u8(b'[');
+    for (n, response) in responses.iter().enumerate() {
+        if n > 0 {
+            out.put_u8(b',');
+        }
+        out.put_slice(response);
+    }
+    out.put_u8(b']');
+    Some(out.freeze())
+}
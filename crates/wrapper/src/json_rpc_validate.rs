@@ -0,0 +1,75 @@
+use jsonrpc_core::ErrorCode;
+use serde_json::{Map, Value};
+
+/// reason a stdin line is not forwarded
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidMessage {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl InvalidMessage {
+    fn request(message: &str) -> Self {
+        Self {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Invalid Request: {message}"),
+        }
+    }
+}
+
+/// checks that a stdin line is a JSON-RPC 2.0 request, notification or response;
+/// batches are checked element by element
+/// # Errors
+/// * `-32700` for malformed JSON, `-32600` for an invalid message
+pub fn validate_message(line: &[u8]) -> Result<(), InvalidMessage> {
+    let value: Value = serde_json::from_slice(line).map_err(|e| InvalidMessage {
+        code: ErrorCode::ParseError,
+        message: format!("Parse error: {e}"),
+    })?;
+    match &value {
+        Value::Array(batch) if batch.is_empty() => Err(InvalidMessage::request("empty batch")),
+        Value::Array(batch) => batch.iter().try_for_each(validate_value),
+        other => validate_value(other),
+    }
+}
+
+fn validate_value(value: &Value) -> Result<(), InvalidMessage> {
+    let Value::Object(message) = value else {
+        return Err(InvalidMessage::request("expected an object"));
+    };
+    if message.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Err(InvalidMessage::request("jsonrpc must be \"2.0\""));
+    }
+    if let Some(id) = message.get("id")
+        && !matches!(id, Value::String(_) | Value::Number(_) | Value::Null)
+    {
+        return Err(InvalidMessage::request(
+            "id must be a string, number or null",
+        ));
+    }
+    match message.get("method") {
+        Some(Value::String(_)) => validate_params(message),
+        Some(_) => Err(InvalidMessage::request("method must be a string")),
+        // responses to server-initiated requests such as sampling
+        None => validate_response(message),
+    }
+}
+
+fn validate_params(message: &Map<String, Value>) -> Result<(), InvalidMessage> {
+    match message.get("params") {
+        None | Some(Value::Object(_) | Value::Array(_)) => Ok(()),
+        Some(_) => Err(InvalidMessage::request("params must be an object or array")),
+    }
+}
+
+fn validate_response(message: &Map<String, Value>) -> Result<(), InvalidMessage> {
+    if !message.contains_key("id") {
+        return Err(InvalidMessage::request("missing method"));
+    }
+    match (message.get("result"), message.get("error")) {
+        (Some(_), None) | (None, Some(Value::Object(_))) => Ok(()),
+        _ => Err(InvalidMessage::request(
+            "response needs either result or error",
+        )),
+    }
+}
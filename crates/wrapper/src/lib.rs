@@ -1,11 +1,40 @@
+pub mod affinity;
+pub mod buffer_pool;
+pub mod check;
+pub mod circuit;
 pub mod config;
+pub mod config_env;
 pub mod config_from_cli;
+pub mod config_from_file;
+pub mod daemon;
+pub mod failover;
 
+pub mod inflight;
+pub mod json_rpc_batch;
 pub mod json_rpc_id;
+pub mod json_rpc_validate;
+pub mod list_cache;
+pub mod listen;
 pub mod logger;
+pub mod logger_json;
 pub mod main_loop;
 pub mod mcp_workers;
+mod mcp_workers_line;
+pub mod metrics;
+pub mod oauth;
+pub mod post_error;
 pub mod post_result;
+pub mod protocol;
+pub mod push_forwarder;
+pub mod rate_limit;
+pub mod record;
+pub mod redact;
+pub mod reload;
+pub mod replay;
+pub mod retry;
+pub mod sequencer;
+pub mod session_store;
+pub mod sse_events;
 
 pub mod mcp_workers_write;
 pub mod stdio_process;
@@ -13,14 +42,26 @@ pub mod stdio_reader;
 pub mod stdio_writer;
 pub mod streamer;
 mod streamer_auth;
+pub mod streamer_batch;
+pub mod streamer_circuit;
 pub mod streamer_error;
+mod streamer_events;
+mod streamer_failover;
+pub mod streamer_headers;
 pub mod streamer_id;
+mod streamer_keepalive;
 pub mod streamer_new;
 pub mod streamer_post;
+mod streamer_protocol;
+mod streamer_reload;
+mod streamer_resume;
+pub mod streamer_retry;
 pub mod streamer_send;
 pub mod streamer_session;
+pub mod streamer_sse;
 
 pub mod http_client;
 pub mod json_rpc_id_fast;
 pub mod main_init;
 pub mod streamer_lines;
+pub mod streamer_mux;
@@ -0,0 +1,109 @@
+use crate::json_rpc_id_fast::{parse_field_fast, parse_id_fast};
+use crate::post_result::PostResult;
+use bytes::Bytes;
+use jsonrpc_core::Id;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// list methods answered from the cache
+const LIST_METHODS: [&str; 4] = [
+    "tools/list",
+    "prompts/list",
+    "resources/list",
+    "resources/templates/list",
+];
+
+/// upstream list results kept for a short time
+#[derive(Debug)]
+pub struct ListCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Value)>>,
+}
+
+impl ListCache {
+    /// `ttl` of zero disables caching
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// cache key of a list request, `None` for anything else
+    #[must_use]
+    pub fn key(&self, payload: &[u8]) -> Option<String> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let Id::Str(method) = parse_field_fast(payload, "method") else {
+            return None;
+        };
+        if !LIST_METHODS.contains(&method.as_str()) {
+            return None;
+        }
+        // pages are cached separately
+        let request: Value = serde_json::from_slice(payload).ok()?;
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        Some(format!("{method} {params}"))
+    }
+
+    /// cached response re-addressed to the id of `payload`
+    #[must_use]
+    pub fn get(&self, key: &str, payload: &[u8]) -> Option<PostResult> {
+        let entries = self.entries.lock().ok()?;
+        let (stored, result) = entries.get(key)?;
+        if stored.elapsed() >= self.ttl {
+            return None;
+        }
+        debug!("Answering {key} from cache");
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": parse_id_fast(payload),
+            "result": result,
+        });
+        Some(PostResult {
+            out: vec![Bytes::from(response.to_string())],
+            sse: false,
+        })
+    }
+
+    /// stores the result of a successful list response
+    pub fn put(&self, key: String, res: &PostResult) {
+        let Some(result) = res
+            .messages()
+            .iter()
+            .filter_map(|msg| serde_json::from_slice::<Value>(msg).ok())
+            .find_map(|mut msg| msg.get_mut("result").map(Value::take))
+        else {
+            return;
+        };
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (Instant::now(), result));
+        }
+    }
+
+    /// drops cached lists announced as changed by a server notification
+    pub fn observe(&self, message: &[u8]) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let Id::Str(method) = parse_field_fast(message, "method") else {
+            return;
+        };
+        let Some(kind) = method
+            .strip_prefix("notifications/")
+            .and_then(|m| m.strip_suffix("/list_changed"))
+        else {
+            return;
+        };
+        let prefix = format!("{kind}/");
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|key, _| !key.starts_with(&prefix));
+        }
+        debug!("Invalidated cached {kind} lists");
+    }
+}
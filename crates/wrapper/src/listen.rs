@@ -0,0 +1,180 @@
+use crate::config::Config;
+use crate::http_client::get_http_client;
+use crate::main_loop::{SessionShared, run_session};
+use crate::metrics::{Metrics, spawn_metrics_reporter};
+use crate::reload::{ReloadSet, spawn_reload_set};
+use crate::streamer::McpStreamClient;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tracing::{debug, error, info, warn};
+
+/// local endpoint accepting stdio-style MCP connections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    /// `unix:/path/to.sock`
+    Unix(PathBuf),
+    /// `pipe:\\.\pipe\name` (Windows)
+    Pipe(String),
+}
+
+/// parses a `--listen` address
+/// # Errors
+/// * unknown scheme or empty path
+pub fn parse_listen(raw: &str) -> Result<ListenAddr, String> {
+    if let Some(path) = raw.strip_prefix("unix:")
+        && !path.is_empty()
+    {
+        return Ok(ListenAddr::Unix(PathBuf::from(path)));
+    }
+    if let Some(name) = raw.strip_prefix("pipe:")
+        && !name.is_empty()
+    {
+        return Ok(ListenAddr::Pipe(name.to_string()));
+    }
+    Err(format!(
+        "Invalid listen address '{raw}': expected unix:/path.sock or pipe:\\\\.\\pipe\\name"
+    ))
+}
+
+/// clap value parser for `--listen`
+/// # Errors
+/// * see [`parse_listen`]
+pub fn listen_arg(raw: &str) -> Result<String, String> {
+    parse_listen(raw).map(|_| raw.to_string())
+}
+
+/// serves every accepted connection like stdin/stdout, all sharing one HTTP
+/// pool, one `--metrics-file` writer and one SIGHUP handler
+/// # Errors
+/// * invalid address, socket in use, bind failure
+pub async fn serve(mut config: Config) -> io::Result<()> {
+    let raw = config.listen.clone().unwrap_or_default();
+    let addr = parse_listen(&raw).map_err(io::Error::other)?;
+    if config.session_file.take().is_some() {
+        warn!("--session-file is ignored with --listen, every connection has its own session");
+    }
+    let http_client = get_http_client(&config).await.map_err(io::Error::other)?;
+
+    let metrics = config
+        .metrics_file
+        .as_ref()
+        .map(|_| Arc::new(Metrics::new(config.concurrency)));
+    let reload = Arc::new(ReloadSet::default());
+    let mut background: Vec<_> = spawn_reload_set(Arc::clone(&reload)).into_iter().collect();
+    if let (Some(metrics), Some(path)) = (&metrics, &config.metrics_file) {
+        let interval = Duration::from_secs(config.metrics_interval.max(1));
+        background.push(spawn_metrics_reporter(
+            Arc::clone(metrics),
+            path.clone(),
+            interval,
+        ));
+    }
+    let shared = SessionShared {
+        http_client: Some(http_client),
+        metrics,
+        reload: Some(reload),
+    };
+
+    let served = match addr {
+        ListenAddr::Unix(path) => serve_unix(&config, &shared, path).await,
+        ListenAddr::Pipe(name) => serve_pipe(&config, &shared, &name).await,
+    };
+    for handle in background {
+        handle.abort();
+    }
+    served
+}
+
+/// runs one connection as its own MCP session
+fn spawn_connection<R, W>(config: &Config, shared: &SessionShared, reader: R, writer: W)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let client = match McpStreamClient::try_new(config.clone()) {
+        Ok(client) => Arc::new(client),
+        Err(e) => {
+            error!("Connection rejected: {e}");
+            return;
+        }
+    };
+    let shared = shared.clone();
+    tokio::spawn(async move {
+        debug!("Connection opened");
+        run_session(client, shared, reader, writer).await;
+        debug!("Connection closed");
+    });
+}
+
+#[cfg(unix)]
+async fn serve_unix(config: &Config, shared: &SessionShared, path: PathBuf) -> io::Result<()> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use tokio::net::{UnixListener, UnixStream};
+
+    // pause after a failed accept, so running out of descriptors does not spin
+    const ACCEPT_RETRY: Duration = Duration::from_millis(100);
+
+    // a socket left behind by a previous run blocks bind, a live one is not ours
+    if std::fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_socket()) {
+        if UnixStream::connect(&path).await.is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("Another process is listening on {}", path.display()),
+            ));
+        }
+        std::fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    // only the owner may talk to the upstream with the wrapper's credentials
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    info!("Listening on unix:{}", path.display());
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let (reader, writer) = stream.into_split();
+                spawn_connection(config, shared, reader, writer);
+            }
+            Err(e) => {
+                warn!("Accept failed: {e}");
+                tokio::time::sleep(ACCEPT_RETRY).await;
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn serve_unix(_config: &Config, _shared: &SessionShared, _path: PathBuf) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "unix sockets are not supported on this platform",
+    ))
+}
+
+#[cfg(windows)]
+async fn serve_pipe(config: &Config, shared: &SessionShared, name: &str) -> io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(name)?;
+    info!("Listening on pipe:{name}");
+    loop {
+        server.connect().await?;
+        // the next instance must exist before the connected one is handed off
+        let connected = std::mem::replace(&mut server, ServerOptions::new().create(name)?);
+        let (reader, writer) = tokio::io::split(connected);
+        spawn_connection(config, shared, reader, writer);
+    }
+}
+
+#[cfg(not(windows))]
+#[allow(clippy::unused_async)] // same signature as the Windows version
+async fn serve_pipe(_config: &Config, _shared: &SessionShared, _name: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "named pipes are only supported on Windows",
+    ))
+}
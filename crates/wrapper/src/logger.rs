@@ -1,14 +1,19 @@
 use crate::config::DEFAULT_LOG_LEVEL;
+use crate::logger_json::{JsonFields, JsonFormat};
+use crate::redact::RedactingWriter;
 use std::fs::File;
 use std::io;
 use std::path::Path;
-use std::sync::{Mutex, Once};
-use tracing::level_filters;
+use std::sync::{Mutex, Once, OnceLock};
+use tracing::{Subscriber, level_filters};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer, Registry, fmt, prelude::*, reload};
 
 static INIT: Once = Once::new();
 static GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
 
 fn open_log_file(path: &str) -> io::Result<File> {
     let log_path = Path::new(path);
@@ -42,7 +47,33 @@ fn build_filter(log_level: Option<&str>) -> EnvFilter {
     }
 }
 
-fn init_logger_once(log_level: Option<&str>, log_file: Option<&str>) {
+/// text or line-delimited JSON output layer, credentials are redacted
+fn fmt_layer<S, W>(writer: W, json: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
+        .with_ansi(false)
+        .with_writer(RedactingWriter(writer));
+    if json {
+        layer
+            .event_format(JsonFormat)
+            .fmt_fields(JsonFields)
+            .boxed()
+    } else {
+        layer.boxed()
+    }
+}
+
+/// level filter that [`set_log_level`] can replace later
+fn reloadable_filter(log_level: Option<&str>) -> reload::Layer<EnvFilter, Registry> {
+    let (filter, handle) = reload::Layer::new(build_filter(log_level));
+    let _ = FILTER.set(handle);
+    filter
+}
+
+fn init_logger_once(log_level: Option<&str>, log_file: Option<&str>, json: bool) {
     let level = log_level.unwrap_or(DEFAULT_LOG_LEVEL);
     if level == "off" {
         return;
@@ -53,8 +84,8 @@ fn init_logger_once(log_level: Option<&str>, log_file: Option<&str>) {
             Ok(file) => {
                 let (non_blocking, guard) = tracing_appender::non_blocking(file);
                 let _ = tracing_subscriber::registry()
-                    .with(build_filter(log_level))
-                    .with(fmt::layer().with_ansi(false).with_writer(non_blocking))
+                    .with(reloadable_filter(log_level))
+                    .with(fmt_layer(non_blocking, json))
                     .try_init();
                 if let Ok(mut guard_lock) = GUARD.lock() {
                     *guard_lock = Some(guard);
@@ -71,8 +102,8 @@ fn init_logger_once(log_level: Option<&str>, log_file: Option<&str>) {
 
     let (non_blocking, guard) = tracing_appender::non_blocking(std::io::stderr());
     let _ = tracing_subscriber::registry()
-        .with(build_filter(log_level))
-        .with(fmt::layer().with_ansi(false).with_writer(non_blocking))
+        .with(reloadable_filter(log_level))
+        .with(fmt_layer(non_blocking, json))
         .try_init();
 
     if let Ok(mut guard_lock) = GUARD.lock() {
@@ -82,7 +113,23 @@ fn init_logger_once(log_level: Option<&str>, log_file: Option<&str>) {
 
 /// initializes logger
 pub fn init_logger(log_level: Option<&str>, log_file: Option<&str>) {
-    INIT.call_once(|| init_logger_once(log_level, log_file));
+    init_logger_format(log_level, log_file, false);
+}
+
+/// initializes logger, `json` selects line-delimited JSON records
+pub fn init_logger_format(log_level: Option<&str>, log_file: Option<&str>, json: bool) {
+    INIT.call_once(|| init_logger_once(log_level, log_file, json));
+}
+
+/// changes the level of a running logger
+/// # Errors
+/// * logging was off at startup, there is no logger to change
+pub fn set_log_level(log_level: &str) -> Result<(), String> {
+    FILTER
+        .get()
+        .ok_or_else(|| "logging is off, restart to enable it".to_string())?
+        .reload(build_filter(Some(log_level)))
+        .map_err(|e| e.to_string())
 }
 
 /// Flushes and shuts down the global logger.
@@ -1,11 +1,90 @@
+use mcp_stdio_wrapper::check::{CHECK_COMMAND, run_check};
+use mcp_stdio_wrapper::daemon::{
+    Role, STOP_COMMAND, current_role, detach, stop, stop_pid_path, supervise,
+};
+use mcp_stdio_wrapper::listen::serve;
 use mcp_stdio_wrapper::main_init::init_main;
 use mcp_stdio_wrapper::main_loop::main_loop;
+use mcp_stdio_wrapper::replay::{REPLAY_COMMAND, Replay};
+use std::path::Path;
+use tokio::io::BufReader;
 use tokio::io::{stdin, stdout};
+use tracing::error;
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[tokio::main]
 async fn main() {
-    let config = init_main(std::env::args());
+    let mut args: Vec<_> = std::env::args_os().collect();
+    if args.get(1).is_some_and(|a| a == CHECK_COMMAND) {
+        args.remove(1);
+        let report = run_check(init_main(args)).await;
+        eprint!("{}", report.summary());
+        println!("{}", serde_json::to_string(&report).unwrap_or_default());
+        std::process::exit(report.exit_code());
+    }
+    if args.get(1).is_some_and(|a| a == REPLAY_COMMAND) {
+        let Some(path) = args.get(2) else {
+            eprintln!("Usage: mcp_stdio_wrapper replay <session.jsonl>");
+            std::process::exit(2);
+        };
+        let replay = match Replay::load(Path::new(path)) {
+            Ok(replay) => replay,
+            Err(e) => {
+                eprintln!("Failed to load {}: {e}", Path::new(path).display());
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = replay.serve(BufReader::new(stdin()), stdout()).await {
+            eprintln!("Replay failed: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|a| a == STOP_COMMAND) {
+        let stopped = match stop_pid_path(&args[2..]) {
+            Ok(path) => stop(&path).await,
+            Err(e) => Err(e),
+        };
+        match stopped {
+            Ok(pid) => eprintln!("Stopped daemon {pid}"),
+            Err(e) => {
+                eprintln!("Stop failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    let config = init_main(args);
+    if config.daemon {
+        match current_role() {
+            Role::Launcher => match detach() {
+                Ok(pid) => {
+                    println!("{pid}");
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Daemon failed to start: {e}");
+                    std::process::exit(1);
+                }
+            },
+            Role::Supervisor => {
+                if let Err(e) = supervise(&config).await {
+                    error!("Supervisor failed: {e}");
+                    std::process::exit(1);
+                }
+                return;
+            }
+            Role::Bridge => {}
+        }
+    }
+    if config.listen.is_some() {
+        if let Err(e) = serve(config).await {
+            error!("Listener failed: {e}");
+            eprintln!("Listener failed: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
     main_loop(config, stdin(), stdout()).await;
 }
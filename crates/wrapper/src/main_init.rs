@@ -1,5 +1,9 @@
 use crate::config::Config;
-use crate::logger::init_logger;
+use crate::config::LOG_FORMAT_JSON;
+use crate::logger::init_logger_format;
+use crate::redact::init_redactor;
+use crate::reload::remember_args;
+use std::ffi::OsString;
 use tracing::debug;
 
 pub fn init_main<I, T>(args: I) -> Config
@@ -7,10 +11,14 @@ where
     I: IntoIterator<Item = T>,
     T: Into<std::ffi::OsString> + Clone,
 {
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    remember_args(&args);
     let config = Config::from_cli(args);
-    init_logger(
+    init_redactor(&config.redact_fields);
+    init_logger_format(
         Some(&config.mcp_wrapper_log_level),
         config.mcp_wrapper_log_file.as_deref(),
+        config.log_format == LOG_FORMAT_JSON,
     );
     debug!("Wrapper config: {config:?}");
     debug!("Start");
@@ -1,10 +1,16 @@
 use crate::config::Config;
-use crate::mcp_workers::spawn_workers;
-use crate::stdio_reader::spawn_reader;
+use crate::http_client::get_http_client;
+use crate::mcp_workers::{WorkerShared, spawn_workers_shared};
+use crate::metrics::{Metrics, spawn_metrics_reporter};
+use crate::push_forwarder::spawn_push_forwarder;
+use crate::reload::{ReloadSet, spawn_reload_on_sighup};
+use crate::stdio_reader::spawn_limited_reader;
 use crate::stdio_writer::spawn_writer;
 use crate::streamer::McpStreamClient;
 use bytes::Bytes;
+use reqwest::Client;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite, BufReader, BufWriter};
 use tracing::{debug, error};
 
@@ -14,14 +20,22 @@ pub(crate) fn channel_capacity(concurrency: usize) -> usize {
     concurrency.max(1) * CHANNEL_CAPACITY_PER_WORKER
 }
 
+/// process-wide state shared by the sessions of `--listen`
+#[derive(Debug, Default, Clone)]
+pub struct SessionShared {
+    /// connection pool used instead of building a new one
+    pub http_client: Option<Client>,
+    /// counters written by the caller's `--metrics-file` reporter
+    pub metrics: Option<Arc<Metrics>>,
+    /// SIGHUP handled by the caller for all sessions
+    pub reload: Option<Arc<ReloadSet>>,
+}
+
 pub async fn main_loop<R, W>(config: Config, reader: R, writer: W)
 where
     R: AsyncRead + Unpin + Send + 'static,
     W: AsyncWrite + Unpin + Send + 'static,
 {
-    let reader = BufReader::with_capacity(256 * 1024, reader);
-    let writer = BufWriter::with_capacity(512 * 1024, writer);
-    let concurrency = config.concurrency;
     let client = match McpStreamClient::try_new(config) {
         Ok(client) => client,
         Err(e) => {
@@ -29,7 +43,24 @@ where
             return;
         }
     };
-    let mcp_client = Arc::new(client);
+    run_session(Arc::new(client), SessionShared::default(), reader, writer).await;
+}
+
+/// bridges one reader/writer pair to the upstream until the reader ends;
+/// what `shared` leaves unset is owned by this session alone
+pub async fn run_session<R, W>(
+    mcp_client: Arc<McpStreamClient>,
+    shared: SessionShared,
+    reader: R,
+    writer: W,
+) where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let reader = BufReader::with_capacity(256 * 1024, reader);
+    let writer = BufWriter::with_capacity(512 * 1024, writer);
+    let concurrency = mcp_client.config.concurrency;
+    let max_line = mcp_client.config.max_line_bytes;
     debug!(
         session_id_present = mcp_client.is_ready(),
         "Mcp client initialized"
@@ -41,16 +72,61 @@ where
     // (Worker -> Writer)
     let (writer_tx, writer_rx) = flume::bounded::<Bytes>(queue_capacity);
 
-    spawn_reader(reader_tx, reader);
+    spawn_limited_reader(reader_tx, reader, max_line);
+
+    // server-initiated messages bypass the workers
+    let push = spawn_push_forwarder(&mcp_client, writer_tx.clone());
+
+    // GET event stream and keep-alive pings share one client
+    let keepalive = Duration::from_secs(mcp_client.config.keepalive);
+    let mut background = Vec::new();
+    if !mcp_client.config.no_event_stream || !keepalive.is_zero() {
+        let client = match &shared.http_client {
+            Some(client) => Ok(client.clone()),
+            None => get_http_client(&mcp_client.config).await,
+        };
+        match client {
+            Ok(client) => {
+                if !mcp_client.config.no_event_stream {
+                    background.extend(mcp_client.spawn_event_stream(client.clone()));
+                }
+                if !keepalive.is_zero() {
+                    background.extend(mcp_client.spawn_keepalive(client, keepalive));
+                }
+            }
+            Err(e) => error!("Event stream and keep-alive disabled: {e}"),
+        }
+    }
+
+    // SIGHUP re-reads auth, headers, retries and log level
+    match &shared.reload {
+        Some(set) => set.add(&mcp_client),
+        None => background.extend(spawn_reload_on_sighup(&mcp_client)),
+    }
 
     // create several workers (limit with concurrenty parameter)
 
-    let worker_handles = spawn_workers(concurrency, &mcp_client, &reader_rx, writer_tx).await;
+    let metrics = mcp_client
+        .config
+        .metrics_file
+        .as_ref()
+        .filter(|_| shared.metrics.is_none())
+        .map(|path| (Arc::new(Metrics::new(concurrency)), path.clone()));
+    let reporter = metrics.as_ref().map(|(metrics, path)| {
+        let interval = Duration::from_secs(mcp_client.config.metrics_interval.max(1));
+        spawn_metrics_reporter(Arc::clone(metrics), path.clone(), interval)
+    });
 
-    let exit = spawn_writer(writer_rx, writer);
+    let workers = WorkerShared {
+        metrics: shared
+            .metrics
+            .or_else(|| metrics.as_ref().map(|(metrics, _)| Arc::clone(metrics))),
+        http_client: shared.http_client,
+    };
+    let worker_handles =
+        spawn_workers_shared(concurrency, &mcp_client, &reader_rx, writer_tx, workers).await;
 
-    // Wait for writer to finish
-    let _ = exit.await;
+    let exit = spawn_writer(writer_rx, writer);
 
     // Wait for all workers to complete and detect panics
     for (i, handle) in worker_handles.into_iter().enumerate() {
@@ -59,6 +135,26 @@ where
         }
     }
 
+    // final snapshot covers the requests since the last interval
+    if let Some(reporter) = reporter {
+        reporter.abort();
+    }
+    if let Some((metrics, path)) = &metrics
+        && let Err(e) = metrics.write_snapshot(path)
+    {
+        error!("Failed to write metrics to {}: {e}", path.display());
+    }
+
+    // no more requests: stop forwarding so the writer can drain and exit
+    for handle in background {
+        handle.abort();
+    }
+    push.abort();
+    let _ = push.await;
+
+    // Wait for writer to finish
+    let _ = exit.await;
+
     debug!("Finish");
 }
 
@@ -1,11 +1,19 @@
+use crate::affinity::SessionAffinity;
+use crate::buffer_pool::buffer_pool;
+use crate::config::Config;
 use crate::http_client::get_http_client;
-use crate::mcp_workers_write::write_output;
+use crate::inflight::InFlight;
+use crate::mcp_workers_line::{LineFeatures, Worker};
+use crate::metrics::Metrics;
+use crate::rate_limit::{RateLimiter, parse_rate};
+use crate::record::Recorder;
+use crate::sequencer::Sequencer;
 use crate::streamer::McpStreamClient;
-use crate::streamer_error::mcp_error;
 use bytes::Bytes;
 use flume::{Receiver, Sender};
+use reqwest::Client;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{Instrument, error, info_span};
 
 /// creates configured number of workers
 /// # Panics
@@ -15,26 +23,71 @@ pub async fn spawn_workers(
     mcp_client: &Arc<McpStreamClient>,
     input_rx: &Receiver<Bytes>,
     output_tx: Sender<Bytes>,
+) -> Vec<tokio::task::JoinHandle<()>> {
+    spawn_workers_shared(
+        concurrency,
+        mcp_client,
+        input_rx,
+        output_tx,
+        WorkerShared::default(),
+    )
+    .await
+}
+
+/// state the workers share with the rest of the process
+#[derive(Debug, Default, Clone)]
+pub struct WorkerShared {
+    /// counters reported with `--metrics-file`
+    pub metrics: Option<Arc<Metrics>>,
+    /// connection pool used instead of building a new one
+    pub http_client: Option<Client>,
+}
+
+/// creates configured number of workers using `shared` metrics and pool
+/// # Panics
+/// when http client build fails
+pub async fn spawn_workers_shared(
+    concurrency: usize,
+    mcp_client: &Arc<McpStreamClient>,
+    input_rx: &Receiver<Bytes>,
+    output_tx: Sender<Bytes>,
+    shared: WorkerShared,
 ) -> Vec<tokio::task::JoinHandle<()>> {
     let mut handles = Vec::with_capacity(concurrency);
+    let WorkerShared {
+        metrics,
+        http_client,
+    } = shared;
 
     // Create a shared client if not using per-worker pools
     let shared_client = if mcp_client.config.http_pool_per_worker {
         None
+    } else if let Some(client) = http_client {
+        Some(client)
     } else {
         get_http_client(&mcp_client.config).await.ok()
     };
 
+    // responses are written in read order with `--ordered`
+    let sequencer = mcp_client
+        .config
+        .ordered
+        .then(|| Arc::new(Sequencer::default()));
+    let features = line_features(&mcp_client.config).await;
+
     // Spawn workers
     for i in 0..concurrency {
         let rx = input_rx.clone();
         let tx = output_tx.clone();
         let mcp = Arc::clone(mcp_client);
         let template = shared_client.clone();
+        let sequencer = sequencer.clone();
+        let features = features.clone();
+        let stats = metrics.as_ref().map_or_else(Arc::default, |m| m.worker(i));
 
-        handles.push(tokio::spawn(async move {
+        let task = Arc::clone(&stats).scope(async move {
             // STEP 3: Each worker gets its client handle here
-            let h_client = match template {
+            let http = match template {
                 Some(existing) => existing, // Use the shared one
                 None => {
                     // Create a fresh one for this specific worker
@@ -47,22 +100,92 @@ pub async fn spawn_workers(
                     }
                 }
             };
-
-            // The Work Loop
-            while let Ok(line) = rx.recv_async().await {
-                match mcp.stream_post(&h_client, line.clone()).await {
-                    Ok(res) => {
-                        write_output(i, &tx, res).await;
-                    }
-                    Err(e) => {
-                        error!("Worker {i}: Post failed: {e}");
-                        mcp_error(&i, &line, &e, &tx).await;
-                    }
-                }
-            }
-        }));
+            let worker = Worker {
+                i,
+                mcp,
+                http,
+                tx,
+                stats,
+                features,
+            };
+            run_worker(&worker, &rx, sequencer.as_deref()).await;
+        });
+        // every record logged by this worker carries its index
+        handles.push(tokio::spawn(
+            task.instrument(info_span!("worker", worker = i)),
+        ));
     }
 
     drop(output_tx);
     handles
 }
+
+/// builds the optional line features enabled in `config`
+async fn line_features(config: &Config) -> LineFeatures {
+    // requests retransmitted by the client while the original is still in flight
+    let inflight = config.dedup_requests.then(|| Arc::new(InFlight::default()));
+
+    // requests beyond the limit wait here, the bounded input queue holds the rest
+    let limiter = config
+        .rate_limit
+        .as_deref()
+        .and_then(|raw| parse_rate(raw).ok())
+        .map(|rate| Arc::new(RateLimiter::new(rate)));
+
+    // exchanges written with `--record`, answered later by `replay`
+    let recorder = config
+        .record
+        .as_deref()
+        .and_then(|path| match Recorder::open(path) {
+            Ok(recorder) => Some(Arc::new(recorder)),
+            Err(e) => {
+                error!("Recording to {} disabled: {e}", path.display());
+                None
+            }
+        });
+
+    // sessions bound to their connection: one sticky client once a session exists
+    let affinity = if config.session_affinity {
+        match SessionAffinity::new(config).await {
+            Ok(affinity) => Some(Arc::new(affinity)),
+            Err(e) => {
+                error!("Session affinity disabled: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    LineFeatures {
+        inflight,
+        limiter,
+        recorder,
+        affinity,
+        validate: !config.no_validate,
+    }
+}
+
+/// answers lines until the input channel closes; a duplicate of a line
+/// stays blocked until its answer is written
+async fn run_worker(worker: &Worker, rx: &Receiver<Bytes>, sequencer: Option<&Sequencer>) {
+    loop {
+        let next = match sequencer {
+            Some(seq) => seq.recv(rx).await,
+            None => rx.recv_async().await.map(|line| (0, line)),
+        };
+        let Ok((ticket, line)) = next else {
+            break;
+        };
+        let admission = worker.admit(&line);
+        let outcome = worker.handle(&line, admission.as_ref()).await;
+
+        let _turn = match sequencer {
+            Some(seq) => Some(seq.turn(ticket).await),
+            None => None,
+        };
+        worker.write(&line, outcome).await;
+        // the reader fills the next line into this buffer when nothing else holds it
+        buffer_pool().recycle(line);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::affinity::SessionAffinity;
+use crate::inflight::{Admission, InFlight};
+use crate::json_rpc_batch::is_batch;
+use crate::json_rpc_id_fast::parse_id_fast;
+use crate::json_rpc_validate::{InvalidMessage, validate_message};
+use crate::mcp_workers_write::write_output;
+use crate::metrics::WorkerMetrics;
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::rate_limit::RateLimiter;
+use crate::record::Recorder;
+use crate::stdio_reader::rejected_reply;
+use crate::streamer::McpStreamClient;
+use crate::streamer_error::{error_response, mcp_error};
+use bytes::Bytes;
+use flume::Sender;
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::MutexGuard;
+use tracing::{debug, error, warn};
+
+/// what a worker answers for one stdin line
+pub(crate) enum Outcome {
+    /// nothing to answer
+    Skip,
+    /// rejected locally
+    Invalid(InvalidMessage),
+    Posted(Result<PostResult, PostError>),
+    /// lines answering a JSON-RPC batch
+    Batch(Vec<Bytes>),
+    /// error reply built by the reader
+    Rejected(Bytes),
+}
+
+/// optional features every worker applies to its lines
+#[derive(Clone, Default)]
+pub(crate) struct LineFeatures {
+    /// requests retransmitted by the client while the original is still in flight
+    pub(crate) inflight: Option<Arc<InFlight>>,
+    /// requests beyond the limit wait here
+    pub(crate) limiter: Option<Arc<RateLimiter>>,
+    /// exchanges written with `--record`
+    pub(crate) recorder: Option<Arc<Recorder>>,
+    /// one sticky connection once a session exists
+    pub(crate) affinity: Option<Arc<SessionAffinity>>,
+    pub(crate) validate: bool,
+}
+
+/// one worker: answers stdin lines through its own client handle
+pub(crate) struct Worker {
+    pub(crate) i: usize,
+    pub(crate) mcp: Arc<McpStreamClient>,
+    pub(crate) http: Client,
+    pub(crate) tx: Sender<Bytes>,
+    pub(crate) stats: Arc<WorkerMetrics>,
+    pub(crate) features: LineFeatures,
+}
+
+impl Worker {
+    /// registers `line` with the duplicate filter; hold the result until it is answered
+    pub(crate) fn admit(&self, line: &Bytes) -> Option<Admission<'_>> {
+        self.features
+            .inflight
+            .as_deref()
+            .filter(|_| rejected_reply(line).is_none())
+            .map(|inflight| inflight.begin(line))
+    }
+
+    /// works out the answer for `line` without writing it
+    pub(crate) async fn handle(&self, line: &Bytes, admission: Option<&Admission<'_>>) -> Outcome {
+        let i = self.i;
+        if let Some(reply) = rejected_reply(line) {
+            self.stats.record_rejected();
+            Outcome::Rejected(reply)
+        } else if let Some(Admission::Duplicate(id)) = admission {
+            warn!("Worker {i}: dropping duplicate of in-flight request {id:?}");
+            Outcome::Skip
+        } else if line.trim_ascii().is_empty() {
+            Outcome::Skip
+        } else if is_batch(line) {
+            self.post_batch(line).await
+        } else if let Some(Err(invalid)) = self.features.validate.then(|| validate_message(line)) {
+            warn!("Worker {i}: rejecting stdin line: {}", invalid.message);
+            self.stats.record_rejected();
+            Outcome::Invalid(invalid)
+        } else {
+            self.post_line(line).await
+        }
+    }
+
+    async fn post_batch(&self, line: &Bytes) -> Outcome {
+        let (http, _lane) = self.session_client().await;
+        let started = Instant::now();
+        let lines = self
+            .mcp
+            .post_batch(
+                http,
+                line,
+                self.features.limiter.as_deref(),
+                self.features.validate,
+            )
+            .await;
+        self.stats.record(started.elapsed(), false);
+        Outcome::Batch(lines)
+    }
+
+    async fn post_line(&self, line: &Bytes) -> Outcome {
+        if let Some(limiter) = self.features.limiter.as_deref() {
+            limiter.acquire().await;
+        }
+        let (http, _lane) = self.session_client().await;
+        let started = Instant::now();
+        let res = self.mcp.post_guarded(http, line.clone()).await;
+        let latency = started.elapsed();
+        self.stats.record(latency, res.is_err());
+        debug!(
+            request_id = %request_id(line),
+            latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+            ok = res.is_ok(),
+            "Request finished"
+        );
+        Outcome::Posted(res)
+    }
+
+    /// sticky session client with its lane held, or this worker's client
+    async fn session_client(&self) -> (&Client, Option<MutexGuard<'_, ()>>) {
+        let sticky = self
+            .features
+            .affinity
+            .as_deref()
+            .filter(|_| self.mcp.is_ready());
+        let lane = match sticky {
+            Some(affinity) => affinity.acquire().await,
+            None => None,
+        };
+        (sticky.map_or(&self.http, SessionAffinity::client), lane)
+    }
+
+    /// writes the answer for `line` to stdout
+    pub(crate) async fn write(&self, line: &Bytes, outcome: Outcome) {
+        let (i, tx) = (self.i, &self.tx);
+        let recorder = self.features.recorder.as_deref();
+        match outcome {
+            Outcome::Skip => {}
+            Outcome::Invalid(invalid) => {
+                let msg = error_response(line, invalid.code, &invalid.message);
+                if let Err(e) = tx.send_async(msg).await {
+                    error!("Worker {i}: failed to send JSON-RPC response: {e}");
+                }
+            }
+            Outcome::Rejected(reply) => {
+                if let Err(e) = tx.send_async(reply).await {
+                    error!("Worker {i}: failed to send JSON-RPC response: {e}");
+                }
+            }
+            Outcome::Batch(lines) => {
+                if let Some(recorder) = recorder {
+                    recorder.record(line, &lines);
+                }
+                for out in lines {
+                    if let Err(e) = tx.send_async(out).await {
+                        error!("Worker {i}: failed to send: {e}");
+                        break;
+                    }
+                }
+            }
+            Outcome::Posted(Ok(res)) => {
+                if let Some(recorder) = recorder {
+                    recorder.record(line, &res.messages());
+                }
+                write_output(i, tx, res).await;
+            }
+            Outcome::Posted(Err(e)) => {
+                error!("Worker {i}: Post failed: {e}");
+                mcp_error(&i, line, &e.to_string(), tx).await;
+            }
+        }
+    }
+}
+
+/// request id as it appears on the wire, for log fields
+fn request_id(line: &[u8]) -> String {
+    serde_json::to_string(&parse_id_fast(line)).unwrap_or_default()
+}
@@ -7,7 +7,7 @@ const DATA: &[u8] = b"data:";
 const DATA_LEN: usize = DATA.len();
 const EMPTY: Bytes = Bytes::new();
 /// Trims leading and trailing ASCII whitespace from input.
-fn trim_ascii_whitespace(bytes: &Bytes) -> Bytes {
+pub(crate) fn trim_ascii_whitespace(bytes: &Bytes) -> Bytes {
     let start = bytes
         .iter()
         .position(|b| !b.is_ascii_whitespace())
@@ -25,7 +25,7 @@ fn trim_ascii_whitespace(bytes: &Bytes) -> Bytes {
     }
 }
 
-fn strip_data_prefix(b: &Bytes) -> Bytes {
+pub(crate) fn strip_data_prefix(b: &Bytes) -> Bytes {
     if b.starts_with(DATA) {
         trim_ascii_whitespace(&b.slice(DATA_LEN..))
     } else {
@@ -34,17 +34,8 @@ fn strip_data_prefix(b: &Bytes) -> Bytes {
 }
 /// writes worker output to stdout channel
 pub async fn write_output(i: usize, tx: &Sender<Bytes>, res: PostResult) {
-    for line in res.out {
-        let out_line = if res.sse {
-            // For SSE, strip "data:"
-            strip_data_prefix(&line)
-        } else {
-            trim_ascii_whitespace(&line)
-        };
-
-        if !out_line.is_empty()
-            && let Err(e) = tx.send_async(out_line).await
-        {
+    for out_line in res.messages() {
+        if let Err(e) = tx.send_async(out_line).await {
             error!("Worker {i}: failed to send: {e}");
             break;
         }
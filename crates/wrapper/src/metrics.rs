@@ -0,0 +1,196 @@
+use crate::buffer_pool::{PoolSnapshot, buffer_pool};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::error;
+
+/// latencies kept per worker for percentiles
+const LATENCY_WINDOW: usize = 1024;
+
+tokio::task_local! {
+    /// counters of the worker running the current task
+    static WORKER: Arc<WorkerMetrics>;
+}
+
+/// counters of a single worker
+#[derive(Debug, Default)]
+pub struct WorkerMetrics {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    retries: AtomicU64,
+    latencies_ms: Mutex<VecDeque<u64>>,
+}
+
+/// counters of all workers
+#[derive(Debug)]
+pub struct Metrics {
+    started: Instant,
+    workers: Vec<Arc<WorkerMetrics>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencySnapshot {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkerSnapshot {
+    pub worker: usize,
+    pub requests: u64,
+    pub errors: u64,
+    pub retries: u64,
+    pub latency_ms: LatencySnapshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetricsSnapshot {
+    /// unix time in seconds
+    pub timestamp: u64,
+    pub uptime_secs: u64,
+    pub requests: u64,
+    pub errors: u64,
+    pub retries: u64,
+    pub workers: Vec<WorkerSnapshot>,
+    /// reuse of stdin line buffers
+    pub buffer_pool: PoolSnapshot,
+}
+
+impl WorkerMetrics {
+    /// records a finished request
+    pub fn record(&self, latency: Duration, failed: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Ok(mut latencies) = self.latencies_ms.lock() {
+            if latencies.len() == LATENCY_WINDOW {
+                latencies.pop_front();
+            }
+            latencies.push_back(u64::try_from(latency.as_millis()).unwrap_or(u64::MAX));
+        }
+    }
+
+    /// records a request rejected before it was sent
+    pub fn record_rejected(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// runs a worker loop with `self` receiving its retry counts
+    pub async fn scope<F: Future>(self: Arc<Self>, f: F) -> F::Output {
+        WORKER.scope(self, f).await
+    }
+
+    fn snapshot(&self, worker: usize) -> WorkerSnapshot {
+        let mut latencies: Vec<u64> = self
+            .latencies_ms
+            .lock()
+            .map(|l| l.iter().copied().collect())
+            .unwrap_or_default();
+        latencies.sort_unstable();
+        WorkerSnapshot {
+            worker,
+            requests: self.requests.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            latency_ms: LatencySnapshot {
+                p50: percentile(&latencies, 50),
+                p90: percentile(&latencies, 90),
+                p99: percentile(&latencies, 99),
+                max: latencies.last().copied().unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// counts a retry for the worker running the current task
+pub fn record_retry() {
+    let _ = WORKER.try_with(|w| w.retries.fetch_add(1, Ordering::Relaxed));
+}
+
+/// nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl Metrics {
+    #[must_use]
+    pub fn new(workers: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            workers: (0..workers).map(|_| Arc::default()).collect(),
+        }
+    }
+
+    /// counters of worker `i`
+    #[must_use]
+    pub fn worker(&self, i: usize) -> Arc<WorkerMetrics> {
+        self.workers.get(i).cloned().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let workers: Vec<WorkerSnapshot> = self
+            .workers
+            .iter()
+            .enumerate()
+            .map(|(i, w)| w.snapshot(i))
+            .collect();
+        MetricsSnapshot {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            requests: workers.iter().map(|w| w.requests).sum(),
+            errors: workers.iter().map(|w| w.errors).sum(),
+            retries: workers.iter().map(|w| w.retries).sum(),
+            workers,
+            buffer_pool: buffer_pool().snapshot(),
+        }
+    }
+
+    /// appends one JSON snapshot line to `path`
+    /// # Errors
+    /// * file can not be written
+    pub fn write_snapshot(&self, path: &Path) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(&self.snapshot())?;
+        line.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&line)
+    }
+}
+
+/// writes a snapshot to `path` every `interval`
+#[must_use]
+pub fn spawn_metrics_reporter(
+    metrics: Arc<Metrics>,
+    path: PathBuf,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = metrics.write_snapshot(&path) {
+                error!("Failed to write metrics to {}: {e}", path.display());
+            }
+        }
+    })
+}
@@ -0,0 +1,109 @@
+use crate::config::Config;
+use crate::streamer_post::read_error_body;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// tokens are refreshed this long before they expire
+const EXPIRY_SKEW: Duration = Duration::from_secs(30);
+/// lifetime assumed when the token endpoint omits `expires_in`
+const DEFAULT_LIFETIME_SECS: u64 = 300;
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct CachedToken {
+    value: String,
+    expires_at: Instant,
+}
+
+/// `OAuth2` client-credentials token source with caching and refresh
+pub struct OAuthClient {
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    scopes: Option<String>,
+    /// `--max-response-bytes`, also applied to token endpoint errors
+    max_response_bytes: usize,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl OAuthClient {
+    /// creates the token source when the client-credentials flow is configured
+    #[must_use]
+    pub fn from_config(config: &Config) -> Option<Self> {
+        Some(Self {
+            token_url: config.oauth_token_url.clone()?,
+            client_id: config.oauth_client_id.clone()?,
+            client_secret: config.oauth_client_secret.clone()?,
+            scopes: config.oauth_scopes.clone(),
+            max_response_bytes: config.max_response_bytes,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// returns the cached access token, fetching a new one when missing or about to expire
+    /// # Errors
+    /// * token endpoint unreachable or returned an error
+    pub async fn access_token(&self, client: &Client) -> Result<String, String> {
+        let mut guard = self.token.lock().await;
+        if let Some(token) = guard.as_ref()
+            && token.expires_at > Instant::now()
+        {
+            return Ok(token.value.clone());
+        }
+        let fresh = self.fetch(client).await?;
+        let value = fresh.value.clone();
+        *guard = Some(fresh);
+        Ok(value)
+    }
+
+    /// drops the cached token so the next request fetches a new one
+    pub async fn invalidate(&self) {
+        *self.token.lock().await = None;
+    }
+
+    async fn fetch(&self, client: &Client) -> Result<CachedToken, String> {
+        let mut form = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        if let Some(scopes) = &self.scopes {
+            form.push(("scope", scopes.as_str()));
+        }
+
+        let response = client
+            .post(&self.token_url)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| format!("Token request failed: {e}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = read_error_body(response, self.max_response_bytes)
+                .await
+                .map_err(|e| format!("Token endpoint error {status}: {e}"))?;
+            return Err(format!("Token endpoint error {status}: {body}"));
+        }
+
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid token response: {e}"))?;
+        let lifetime = Duration::from_secs(token.expires_in.unwrap_or(DEFAULT_LIFETIME_SECS));
+        debug!(
+            expires_in = lifetime.as_secs(),
+            "OAuth access token acquired"
+        );
+        Ok(CachedToken {
+            value: token.access_token,
+            expires_at: Instant::now() + lifetime.saturating_sub(EXPIRY_SKEW),
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::Duration;
+
+/// failure of a single upstream exchange
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostError {
+    /// request could not be sent or the response stream broke off
+    Transport(String),
+    /// no complete response within `--request-timeout`
+    Timeout(Duration),
+    /// upstream answered with a non-success status
+    Status { status: StatusCode, body: String },
+    /// local failures: credentials, routing, protocol
+    Other(String),
+}
+
+impl fmt::Display for PostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(e) | Self::Other(e) => f.write_str(e),
+            Self::Timeout(limit) => write!(f, "Request timed out after {}s", limit.as_secs()),
+            Self::Status { status, body } => write!(f, "Server error {status}: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for PostError {}
+
+impl From<String> for PostError {
+    fn from(e: String) -> Self {
+        Self::Other(e)
+    }
+}
+
+impl From<&str> for PostError {
+    fn from(e: &str) -> Self {
+        Self::Other(e.to_string())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::mcp_workers_write::{strip_data_prefix, trim_ascii_whitespace};
 use bytes::Bytes;
 
 #[derive(Debug, Clone)]
@@ -9,3 +10,22 @@ pub struct PostResult {
     /// http event flag
     pub sse: bool,
 }
+
+impl PostResult {
+    /// JSON-RPC messages of the response with SSE framing removed
+    #[must_use]
+    pub fn messages(&self) -> Vec<Bytes> {
+        self.out
+            .iter()
+            .map(|line| {
+                if self.sse {
+                    // For SSE, strip "data:"
+                    strip_data_prefix(line)
+                } else {
+                    trim_ascii_whitespace(line)
+                }
+            })
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
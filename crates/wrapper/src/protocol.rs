@@ -0,0 +1,64 @@
+use serde_json::Value;
+
+/// header carrying the negotiated version on every request after initialize
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// MCP protocol versions the wrapper knows, oldest first
+pub const SUPPORTED_VERSIONS: [&str; 4] = ["2024-11-05", "2025-03-26", "2025-06-18", "2025-11-25"];
+
+/// first version with the version header and client elicitation
+const HEADER_SINCE: &str = "2025-06-18";
+
+/// versions are dates, so they order as strings
+#[must_use]
+pub fn sends_version_header(version: &str) -> bool {
+    version >= HEADER_SINCE
+}
+
+/// highest version both sides support that is not newer than `requested`
+#[must_use]
+pub fn negotiate(requested: &str, server: &[String]) -> Option<String> {
+    server
+        .iter()
+        .filter(|v| v.as_str() <= requested)
+        .filter(|v| v.as_str() == requested || SUPPORTED_VERSIONS.contains(&v.as_str()))
+        .max()
+        .cloned()
+}
+
+/// `protocolVersion` of an initialize request or result object
+#[must_use]
+pub fn protocol_version(params: &Value) -> Option<&str> {
+    params.get("protocolVersion").and_then(Value::as_str)
+}
+
+/// versions listed by a server refusing the requested one:
+/// a JSON-RPC error with `data.supported`
+#[must_use]
+pub fn supported_versions(message: &Value) -> Option<Vec<String>> {
+    let supported = message.get("error")?.get("data")?.get("supported")?;
+    let versions = supported
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    (!versions.is_empty()).then_some(versions)
+}
+
+/// initialize request asking for `version`, without capabilities that version lacks
+#[must_use]
+pub fn downgrade_initialize(request: &Value, version: &str) -> Value {
+    let mut request = request.clone();
+    if let Some(params) = request.get_mut("params").and_then(Value::as_object_mut) {
+        params.insert("protocolVersion".to_string(), Value::from(version));
+        if !sends_version_header(version)
+            && let Some(capabilities) = params
+                .get_mut("capabilities")
+                .and_then(Value::as_object_mut)
+        {
+            capabilities.remove("elicitation");
+        }
+    }
+    request
+}
@@ -0,0 +1,25 @@
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use flume::Sender;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+/// forwards server-initiated messages to the stdout writer
+pub fn spawn_push_forwarder(
+    mcp_client: &Arc<McpStreamClient>,
+    tx: Sender<Bytes>,
+) -> JoinHandle<()> {
+    let rx = mcp_client.push_rx.clone();
+    let mcp = Arc::clone(mcp_client);
+    tokio::spawn(async move {
+        while let Ok(message) = rx.recv_async().await {
+            mcp.list_cache.observe(&message);
+            if let Err(e) = tx.send_async(message).await {
+                error!("Failed to forward server message: {e}");
+                break;
+            }
+        }
+        debug!("Push forwarder terminated");
+    })
+}
@@ -0,0 +1,75 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// token bucket shared by all workers
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// tokens per second
+    rate: f64,
+    /// bucket size, allows a burst of one second worth of requests
+    burst: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+/// parses `N/s`, `N/m`, `N/h` or plain `N` (per second) into requests per second
+/// # Errors
+/// * malformed or non-positive rate
+pub fn parse_rate(raw: &str) -> Result<f64, String> {
+    let (count, unit) = raw.trim().split_once('/').unwrap_or((raw.trim(), "s"));
+    let seconds = match unit.trim() {
+        "s" | "sec" => 1.0,
+        "m" | "min" => 60.0,
+        "h" | "hour" => 3600.0,
+        other => return Err(format!("Invalid rate unit '{other}': expected s, m or h")),
+    };
+    let count: f64 = count
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid rate '{raw}': {e}"))?;
+    if !count.is_finite() || count <= 0.0 {
+        return Err(format!("Invalid rate '{raw}': must be positive"));
+    }
+    Ok(count / seconds)
+}
+
+/// clap value parser for `--rate-limit`
+/// # Errors
+/// * see [`parse_rate`]
+pub fn rate_arg(raw: &str) -> Result<String, String> {
+    parse_rate(raw).map(|_| raw.to_string())
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(rate: f64) -> Self {
+        let burst = rate.max(1.0);
+        Self {
+            rate,
+            burst,
+            state: Mutex::new((burst, Instant::now())),
+        }
+    }
+
+    /// takes a token, `Err` holds the time until the next one is available
+    fn try_acquire(&self) -> Result<(), Duration> {
+        let Ok(mut state) = self.state.lock() else {
+            return Ok(());
+        };
+        let (tokens, last) = &mut *state;
+        let now = Instant::now();
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.rate).min(self.burst);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64((1.0 - *tokens) / self.rate))
+    }
+
+    /// waits until a request may be sent
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::redact::redact;
+use bytes::Bytes;
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
+
+/// appends request/response pairs to a `--record` file, one JSON object per line
+#[derive(Debug)]
+pub struct Recorder {
+    file: Mutex<File>,
+}
+
+impl Recorder {
+    /// opens `path` for appending, readable by the owner only
+    /// # Errors
+    /// * file can not be created
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        Ok(Self {
+            file: Mutex::new(options.open(path)?),
+        })
+    }
+
+    /// records `request` and the messages written for it, credentials redacted
+    pub fn record(&self, request: &[u8], responses: &[Bytes]) {
+        let line = record_line(request, responses);
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            error!("Failed to record traffic: {e}");
+        }
+    }
+}
+
+/// one recorded exchange as a redacted JSON line
+#[must_use]
+pub fn record_line(request: &[u8], responses: &[Bytes]) -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+    let entry = json!({
+        "ts": ts,
+        "request": to_value(request),
+        "responses": responses.iter().map(|r| to_value(r)).collect::<Vec<_>>(),
+    });
+    let mut line = redact(&entry.to_string()).into_owned();
+    line.push('\n');
+    line
+}
+
+/// JSON when the bytes parse, the lossy text otherwise
+fn to_value(message: &[u8]) -> Value {
+    serde_json::from_slice(message)
+        .unwrap_or_else(|_| Value::from(String::from_utf8_lossy(message).into_owned()))
+}
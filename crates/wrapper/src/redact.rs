@@ -0,0 +1,126 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::io;
+use std::sync::OnceLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// replacement for every redacted value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// field and query parameter names that are always redacted
+pub const DEFAULT_REDACT_FIELDS: [&str; 10] = [
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "token",
+    "api_key",
+    "apikey",
+    "x-api-key",
+    "client_secret",
+    "password",
+    "secret",
+];
+
+static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+
+/// masks credentials in free text: auth headers, bearer tokens,
+/// URL user info and `name=value` / `"name": "value"` pairs,
+/// also when the quotes are escaped inside a JSON log record
+pub struct Redactor {
+    header: Regex,
+    bearer: Regex,
+    userinfo: Regex,
+    fields: Regex,
+}
+
+impl Redactor {
+    /// builds a redactor for the default names plus `extra_fields`
+    ///
+    /// # Panics
+    /// * never, every pattern is built from escaped literals
+    #[must_use]
+    pub fn new(extra_fields: &[String]) -> Self {
+        let names = DEFAULT_REDACT_FIELDS
+            .iter()
+            .copied()
+            .chain(extra_fields.iter().map(String::as_str))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join("|");
+        Self {
+            header: Regex::new(
+                r#"(?i)\b((?:proxy-)?authorization\\?["']?\s*[:=]\s*\\?["']?)([^"'\\\r\n,}]+)"#,
+            )
+            .expect("valid header pattern"),
+            bearer: Regex::new(r"(?i)\b(bearer|basic)\s+[A-Za-z0-9\-._~+/]+=*")
+                .expect("valid bearer pattern"),
+            userinfo: Regex::new(r"(?i)\b([a-z][a-z0-9+.\-]*://)[^/@\s]+@")
+                .expect("valid userinfo pattern"),
+            fields: Regex::new(&format!(
+                r#"(?i)((?:^|[^\w-])\\?["']?(?:{names})\\?["']?\s*[:=]\s*\\?["']?)([^"'\\&\s,}}]+)"#
+            ))
+            .expect("valid field pattern"),
+        }
+    }
+
+    /// returns `text` with every credential replaced by [`REDACTED`]
+    #[must_use]
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        for (re, with) in [
+            (&self.bearer, "$1 [REDACTED]"),
+            (&self.header, "${1}[REDACTED]"),
+            (&self.userinfo, "${1}[REDACTED]@"),
+            (&self.fields, "${1}[REDACTED]"),
+        ] {
+            let replaced = match re.replace_all(&out, with) {
+                Cow::Owned(replaced) => Some(replaced),
+                Cow::Borrowed(_) => None,
+            };
+            if let Some(replaced) = replaced {
+                out = Cow::Owned(replaced);
+            }
+        }
+        out
+    }
+}
+
+/// installs the process-wide redactor, only the first call has effect
+pub fn init_redactor(extra_fields: &[String]) {
+    let _ = REDACTOR.set(Redactor::new(extra_fields));
+}
+
+/// redacts `text` with the process-wide redactor
+#[must_use]
+pub fn redact(text: &str) -> Cow<'_, str> {
+    REDACTOR.get_or_init(|| Redactor::new(&[])).redact(text)
+}
+
+/// log writer that redacts every formatted record before writing it
+pub struct RedactingWriter<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingWriter<M> {
+    type Writer = Redacting<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        Redacting(self.0.make_writer())
+    }
+}
+
+/// single record writer returned by [`RedactingWriter`]
+pub struct Redacting<W>(W);
+
+impl<W: io::Write> io::Write for Redacting<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // the fmt layer hands over one complete record per write
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
@@ -0,0 +1,113 @@
+use crate::config::Config;
+use crate::logger::set_log_level;
+use crate::streamer::McpStreamClient;
+use std::ffi::OsString;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+static ARGS: OnceLock<Vec<OsString>> = OnceLock::new();
+
+/// keeps the command line so the config can be read again on SIGHUP
+pub fn remember_args(args: &[OsString]) {
+    let _ = ARGS.set(args.to_vec());
+}
+
+/// reads the config file and environment again with the original command line
+/// # Errors
+/// * no command line remembered, or the new config is invalid
+pub fn reload_config() -> Result<Config, String> {
+    let args = ARGS.get().ok_or("No command line to reload from")?;
+    Config::try_from_cli(args.clone()).map_err(|e| e.to_string())
+}
+
+/// applies a reloaded config: auth, headers, retries and log level
+/// # Errors
+/// * config can not be read or has an invalid header
+pub fn apply_reload(client: &McpStreamClient) -> Result<(), String> {
+    apply_config(&reload_config()?, [client])
+}
+
+/// sessions reloaded by one SIGHUP handler; closed sessions drop out
+#[derive(Debug, Default)]
+pub struct ReloadSet(Mutex<Vec<Weak<McpStreamClient>>>);
+
+impl ReloadSet {
+    /// reloads `client` with the others until it is dropped
+    pub fn add(&self, client: &Arc<McpStreamClient>) {
+        if let Ok(mut clients) = self.0.lock() {
+            clients.retain(|weak| weak.strong_count() > 0);
+            clients.push(Arc::downgrade(client));
+        }
+    }
+
+    fn live(&self) -> Vec<Arc<McpStreamClient>> {
+        self.0
+            .lock()
+            .map(|clients| clients.iter().filter_map(Weak::upgrade).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// [`apply_reload`] for every live session of `set`, reading the config once
+/// # Errors
+/// * config can not be read or has an invalid header
+pub fn apply_reload_all(set: &ReloadSet) -> Result<(), String> {
+    let config = reload_config()?;
+    apply_config(&config, set.live().iter().map(Arc::as_ref))
+}
+
+fn apply_config<'a>(
+    config: &Config,
+    clients: impl IntoIterator<Item = &'a McpStreamClient>,
+) -> Result<(), String> {
+    for client in clients {
+        client.reload(config)?;
+    }
+    if let Err(e) = set_log_level(&config.mcp_wrapper_log_level) {
+        warn!("Log level not reloaded: {e}");
+    }
+    Ok(())
+}
+
+/// reloads the config on every SIGHUP; `None` where there are no signals
+/// or no command line was remembered
+#[must_use]
+pub fn spawn_reload_on_sighup(client: &Arc<McpStreamClient>) -> Option<JoinHandle<()>> {
+    let set = ReloadSet::default();
+    set.add(client);
+    spawn_reload_set(Arc::new(set))
+}
+
+/// one SIGHUP handler for all sessions added to `set`, as with `--listen`
+#[must_use]
+pub fn spawn_reload_set(set: Arc<ReloadSet>) -> Option<JoinHandle<()>> {
+    ARGS.get()?;
+    spawn_listener(set)
+}
+
+#[cfg(unix)]
+fn spawn_listener(set: Arc<ReloadSet>) -> Option<JoinHandle<()>> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!("SIGHUP reload disabled: {e}");
+            return None;
+        }
+    };
+    Some(tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match apply_reload_all(&set) {
+                Ok(()) => info!("Configuration reloaded"),
+                Err(e) => error!("Configuration reload failed, keeping the old one: {e}"),
+            }
+        }
+    }))
+}
+
+#[cfg(not(unix))]
+fn spawn_listener(_set: Arc<ReloadSet>) -> Option<JoinHandle<()>> {
+    None
+}
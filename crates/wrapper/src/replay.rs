@@ -0,0 +1,191 @@
+use crate::json_rpc_batch::{is_batch, is_response, join_batch, split_batch};
+use crate::streamer_error::error_response;
+use bytes::Bytes;
+use jsonrpc_core::ErrorCode;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// argv[1] that switches the binary into replay mode
+pub const REPLAY_COMMAND: &str = "replay";
+
+/// one line of a `--record` file
+#[derive(Debug, Clone, Deserialize)]
+struct Recorded {
+    request: Value,
+    #[serde(default)]
+    responses: Vec<Value>,
+}
+
+/// answers stdin from a `--record` file without contacting the upstream
+#[derive(Debug, Default)]
+pub struct Replay {
+    answers: Mutex<HashMap<String, VecDeque<Recorded>>>,
+}
+
+impl Replay {
+    /// loads a recording, batches are split into their entries
+    /// # Errors
+    /// * file can not be read or a line is not a recorded exchange
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        let mut answers: HashMap<String, VecDeque<Recorded>> = HashMap::new();
+        for (n, line) in raw.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded: Recorded = serde_json::from_str(line).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {e}", n + 1))
+            })?;
+            for exchange in split_recorded(recorded) {
+                if let Some(key) = replay_key(&exchange.request) {
+                    answers.entry(key).or_default().push_back(exchange);
+                }
+            }
+        }
+        Ok(Self {
+            answers: Mutex::new(answers),
+        })
+    }
+
+    /// number of distinct recorded requests
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.answers.lock().map_or(0, |answers| answers.len())
+    }
+
+    /// no recorded requests
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// lines to write for one stdin line
+    #[must_use]
+    pub fn answer(&self, line: &[u8]) -> Vec<Bytes> {
+        if !is_batch(line) {
+            return self.answer_one(line);
+        }
+        let Ok(entries) = split_batch(line) else {
+            return vec![error_response(line, ErrorCode::ParseError, "Parse error")];
+        };
+        let (responses, mut lines): (Vec<_>, Vec<_>) = entries
+            .iter()
+            .flat_map(|entry| self.answer_one(entry))
+            .partition(|message| is_response(message));
+        lines.extend(join_batch(&responses));
+        lines
+    }
+
+    fn answer_one(&self, line: &[u8]) -> Vec<Bytes> {
+        let Ok(request) = serde_json::from_slice::<Value>(line) else {
+            return vec![error_response(line, ErrorCode::ParseError, "Parse error")];
+        };
+        // responses to server requests are not answered
+        let Some(key) = replay_key(&request) else {
+            return Vec::new();
+        };
+        let id = request.get("id");
+        match (self.next(&key), id) {
+            (Some(recorded), _) => recorded
+                .responses
+                .into_iter()
+                .map(|mut response| {
+                    // the recorded response answers the recorded id
+                    if let Some(id) = id
+                        && response.get("method").is_none()
+                        && response.get("id") == recorded.request.get("id")
+                    {
+                        response["id"] = id.clone();
+                    }
+                    Bytes::from(response.to_string())
+                })
+                .collect(),
+            (None, Some(_)) => {
+                let method = request["method"].as_str().unwrap_or_default();
+                vec![error_response(
+                    line,
+                    ErrorCode::MethodNotFound,
+                    &format!("No recorded response for {method}"),
+                )]
+            }
+            (None, None) => Vec::new(),
+        }
+    }
+
+    /// recorded exchanges are used in order, the last one is kept for repeats
+    fn next(&self, key: &str) -> Option<Recorded> {
+        let mut answers = self.answers.lock().ok()?;
+        let queue = answers.get_mut(key)?;
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+
+    /// answers every line of `reader` until EOF
+    /// # Errors
+    /// * reading stdin or writing stdout fails
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> io::Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            for out in self.answer(line.as_bytes()) {
+                writer.write_all(&out).await?;
+                writer.write_all(b"\n").await?;
+            }
+            writer.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+/// method and params identify a request, ids and `_meta` differ between runs
+fn replay_key(request: &Value) -> Option<String> {
+    let method = request.get("method")?.as_str()?;
+    let mut params = request.get("params").cloned().unwrap_or(Value::Null);
+    if let Some(params) = params.as_object_mut() {
+        params.remove("_meta");
+    }
+    Some(format!("{method} {params}"))
+}
+
+/// a recorded batch becomes one exchange per entry
+fn split_recorded(recorded: Recorded) -> Vec<Recorded> {
+    let Value::Array(entries) = recorded.request else {
+        return vec![recorded];
+    };
+    let responses: Vec<Value> = recorded
+        .responses
+        .into_iter()
+        .flat_map(|response| match response {
+            Value::Array(batch) => batch,
+            other => vec![other],
+        })
+        .collect();
+    entries
+        .into_iter()
+        .map(|request| {
+            let responses = match request.get("id") {
+                Some(id) => responses
+                    .iter()
+                    .filter(|r| r.get("method").is_none() && r.get("id") == Some(id))
+                    .cloned()
+                    .collect(),
+                None => Vec::new(),
+            };
+            Recorded { request, responses }
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::post_error::PostError;
+use rand::Rng;
+use std::time::Duration;
+
+/// when and how long to wait before re-sending a failed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base: Duration,
+    pub max: Duration,
+    pub statuses: Vec<u16>,
+    pub idempotent_only: bool,
+}
+
+impl RetryPolicy {
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base: Duration::from_millis(config.retry_base_ms),
+            max: Duration::from_millis(config.retry_max_ms),
+            statuses: config.retry_status.clone(),
+            idempotent_only: !config.retry_non_idempotent,
+        }
+    }
+
+    /// Returns `true` if `attempt` (0-based) may be followed by another one
+    #[must_use]
+    pub fn should_retry(&self, attempt: u32, error: &PostError, method: Option<&str>) -> bool {
+        if attempt >= self.max_retries {
+            return false;
+        }
+        if self.idempotent_only && !method.is_some_and(is_idempotent) {
+            return false;
+        }
+        match error {
+            PostError::Transport(_) | PostError::Timeout(_) => true,
+            PostError::Status { status, .. } => self.statuses.contains(&status.as_u16()),
+            PostError::Other(_) => false,
+        }
+    }
+
+    /// exponential backoff with jitter, capped at `max`
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max);
+        let half = exp / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+}
+
+/// methods that can be repeated without side effects
+#[must_use]
+pub fn is_idempotent(method: &str) -> bool {
+    matches!(
+        method,
+        "initialize" | "ping" | "resources/read" | "prompts/get" | "completion/complete"
+    ) || method.ends_with("/list")
+}
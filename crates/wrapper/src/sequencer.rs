@@ -0,0 +1,52 @@
+use bytes::Bytes;
+use flume::{Receiver, RecvError};
+use tokio::sync::{Mutex, watch};
+
+/// hands out tickets in read order and lets workers write in ticket order
+#[derive(Debug)]
+pub struct Sequencer {
+    next: Mutex<u64>,
+    turn: watch::Sender<u64>,
+}
+
+/// permission to write; passes the turn on when dropped
+#[derive(Debug)]
+pub struct Turn<'a> {
+    sequencer: &'a Sequencer,
+}
+
+impl Default for Sequencer {
+    fn default() -> Self {
+        Self {
+            next: Mutex::new(0),
+            turn: watch::Sender::new(0),
+        }
+    }
+}
+
+impl Sequencer {
+    /// receives the next request together with its ticket
+    /// # Errors
+    /// * input channel closed
+    pub async fn recv(&self, rx: &Receiver<Bytes>) -> Result<(u64, Bytes), RecvError> {
+        let mut next = self.next.lock().await;
+        let line = rx.recv_async().await?;
+        let ticket = *next;
+        *next += 1;
+        Ok((ticket, line))
+    }
+
+    /// waits until all requests read before `ticket` were answered
+    pub async fn turn(&self, ticket: u64) -> Turn<'_> {
+        let mut rx = self.turn.subscribe();
+        // the sender lives in self, so the channel can not close here
+        let _ = rx.wait_for(|turn| *turn == ticket).await;
+        Turn { sequencer: self }
+    }
+}
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        self.sequencer.turn.send_modify(|turn| *turn += 1);
+    }
+}
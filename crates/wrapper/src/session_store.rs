@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// negotiated session persisted between wrapper runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// upstream the session belongs to
+    pub url: String,
+    pub session_id: String,
+    /// original initialize request, replayed when the session is rejected
+    pub initialize_request: Value,
+    /// initialize result, answered locally on resume
+    pub initialize_result: Value,
+}
+
+/// loads a saved session, `None` when missing or unreadable
+#[must_use]
+pub fn load_session(path: &Path) -> Option<SessionState> {
+    let raw = match std::fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) => {
+            debug!("No saved session in {}: {e}", path.display());
+            return None;
+        }
+    };
+    match serde_json::from_slice(&raw) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            warn!("Ignoring invalid session file {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// writes the session file, readable by the owner only
+/// # Errors
+/// * file can not be written
+pub fn save_session(path: &Path, state: &SessionState) -> std::io::Result<()> {
+    let json = serde_json::to_vec_pretty(state)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(&json)
+}
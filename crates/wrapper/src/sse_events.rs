@@ -0,0 +1,109 @@
+use crate::streamer_error::response_limit_error;
+use crate::streamer_lines::extract_lines;
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use reqwest::Response;
+use std::pin::Pin;
+
+const DATA: &[u8] = b"data:";
+const EVENT: &[u8] = b"event:";
+const DEFAULT_EVENT: &str = "message";
+
+type ByteStream = Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>;
+
+/// single server-sent event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// event type, `message` when not named
+    pub event: String,
+    /// payload of the `data:` field
+    pub data: Bytes,
+}
+
+/// Line-based SSE parser.
+/// Blank lines are dropped by `extract_lines`, so every `data:` line
+/// completes an event; JSON-RPC messages never span several data lines.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    event: Option<String>,
+}
+
+impl SseParser {
+    /// feeds one line, returns an event when the line completes one
+    pub fn feed(&mut self, line: &Bytes) -> Option<SseEvent> {
+        if let Some(name) = line.strip_prefix(EVENT) {
+            self.event = Some(String::from_utf8_lossy(name).trim().to_string());
+            return None;
+        }
+        let data = line.strip_prefix(DATA)?;
+        let start = data.iter().take_while(|b| b.is_ascii_whitespace()).count();
+        let data = line.slice(DATA.len() + start..);
+        Some(SseEvent {
+            event: self
+                .event
+                .take()
+                .unwrap_or_else(|| DEFAULT_EVENT.to_string()),
+            data,
+        })
+    }
+}
+
+/// reads events from a long-lived `text/event-stream` response
+pub struct EventReader {
+    stream: ByteStream,
+    buffer: BytesMut,
+    lines: Vec<Bytes>,
+    pos: usize,
+    parser: SseParser,
+    max_line: usize,
+}
+
+impl EventReader {
+    #[must_use]
+    pub fn new(response: Response) -> Self {
+        Self {
+            stream: Box::pin(response.bytes_stream()),
+            buffer: BytesMut::new(),
+            lines: Vec::new(),
+            pos: 0,
+            parser: SseParser::default(),
+            max_line: 0,
+        }
+    }
+
+    /// fails the stream when a single event line exceeds `max_line` bytes (0 = unlimited)
+    #[must_use]
+    pub fn with_limit(mut self, max_line: usize) -> Self {
+        self.max_line = max_line;
+        self
+    }
+
+    /// next complete event, `None` when the stream ends
+    /// # Errors
+    /// * stream interrupted
+    pub async fn next_event(&mut self) -> Result<Option<SseEvent>, String> {
+        loop {
+            while self.pos < self.lines.len() {
+                let line = &self.lines[self.pos];
+                self.pos += 1;
+                if let Some(event) = self.parser.feed(line) {
+                    return Ok(Some(event));
+                }
+            }
+            self.lines.clear();
+            self.pos = 0;
+
+            match self.stream.next().await {
+                Some(Ok(chunk)) => {
+                    self.buffer.extend_from_slice(&chunk);
+                    extract_lines(&mut self.buffer, &mut self.lines);
+                    if self.max_line != 0 && self.buffer.len() > self.max_line {
+                        return Err(response_limit_error(self.max_line));
+                    }
+                }
+                Some(Err(e)) => return Err(format!("Stream interrupted: {e}")),
+                None => return Ok(None),
+            }
+        }
+    }
+}
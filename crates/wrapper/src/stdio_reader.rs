@@ -1,8 +1,11 @@
-use bytes::Bytes;
+use crate::buffer_pool::buffer_pool;
+use crate::streamer_error::{error_response, line_limit_error};
+use bytes::{Bytes, BytesMut};
 use flume::Sender;
-use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use jsonrpc_core::ErrorCode;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::task::JoinHandle;
-use tracing::debug;
+use tracing::{debug, error, warn};
 
 /// stdio reader
 pub fn spawn_reader<R>(tx: Sender<Bytes>, reader: R) -> JoinHandle<()>
@@ -22,3 +25,104 @@ where
         debug!("Exit reader loop");
     })
 }
+
+/// stdin lines never contain their terminator, so a leading newline marks
+/// an error reply the reader built for a rejected line
+const REJECTED: u8 = b'\n';
+
+/// error reply queued by the reader in place of a rejected line; the workers
+/// write it like a response so that `--ordered` keeps it in read order
+#[must_use]
+pub fn rejected_reply(line: &Bytes) -> Option<Bytes> {
+    (line.first() == Some(&REJECTED)).then(|| line.slice(1..))
+}
+
+/// stdio reader rejecting lines longer than `max_line` bytes (0 = unlimited);
+/// oversized requests are queued as a [`rejected_reply`];
+/// lines are read into pooled buffers that the workers hand back
+pub fn spawn_limited_reader<R>(tx: Sender<Bytes>, reader: R, max_line: usize) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let pool = buffer_pool();
+        let mut line = pool.get();
+
+        loop {
+            let fits = match read_limited_line(&mut reader, &mut line, max_line).await {
+                Ok(Some(fits)) => fits,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to read stdin: {e}");
+                    break;
+                }
+            };
+            debug!(line_len = line.len(), "Read MCP line");
+            let sent = if fits {
+                let full = std::mem::replace(&mut line, pool.get());
+                tx.send_async(full.freeze()).await
+            } else {
+                // the id is usually near the start of the kept prefix
+                warn!("Dropping request longer than {max_line} bytes");
+                let msg = line_limit_error(max_line);
+                let reply = error_response(&line, ErrorCode::InvalidRequest, &msg);
+                let mut queued = BytesMut::with_capacity(reply.len() + 1);
+                queued.extend_from_slice(&[REJECTED]);
+                queued.extend_from_slice(&reply);
+                tx.send_async(queued.freeze()).await
+            };
+            if sent.is_err() {
+                debug!("Reader loop terminated");
+                break;
+            }
+        }
+        pool.put(line);
+        debug!("Exit reader loop");
+    })
+}
+
+/// reads one line without its terminator into `line`, keeping at most `max` bytes
+/// Returns `None` at EOF and `Some(false)` if the line was longer than `max`
+async fn read_limited_line<R>(
+    reader: &mut R,
+    line: &mut BytesMut,
+    max: usize,
+) -> std::io::Result<Option<bool>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    line.clear();
+    let mut fits = true;
+    let mut read_any = false;
+    loop {
+        let buf = reader.fill_buf().await?;
+        if buf.is_empty() {
+            break;
+        }
+        read_any = true;
+        let (chunk, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(pos) => (&buf[..pos], true),
+            None => (buf, false),
+        };
+        let used = chunk.len() + usize::from(done);
+        if max != 0 && line.len() + chunk.len() > max {
+            let room = max.saturating_sub(line.len());
+            line.extend_from_slice(&chunk[..room]);
+            fits = false;
+        } else {
+            line.extend_from_slice(chunk);
+        }
+        reader.consume(used);
+        if done {
+            break;
+        }
+    }
+    if !read_any {
+        return Ok(None);
+    }
+    if fits && line.last() == Some(&b'\r') {
+        line.truncate(line.len() - 1);
+    }
+    Ok(Some(fits))
+}
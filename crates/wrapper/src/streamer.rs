@@ -1,28 +1,78 @@
+use crate::circuit::CircuitBreaker;
 use crate::config::Config;
+use crate::failover::Failover;
+use crate::list_cache::ListCache;
+use crate::oauth::OAuthClient;
+use crate::retry::RetryPolicy;
+use crate::session_store::SessionState;
+use crate::streamer_mux::{ServerRequests, Upstream};
+use crate::streamer_sse::SseSession;
 use arc_swap::ArcSwap;
+use bytes::Bytes;
+use flume::{Receiver, Sender};
 use reqwest::header::HeaderMap;
 use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::time::Instant;
 
 pub const SID: &str = "mcp-session-id";
 
 pub struct McpStreamClient {
     //pub(crate) client: Client,
     pub(crate) session_id: ArcSwap<Option<String>>,
+    /// wakes the event stream once a session exists
+    pub(crate) session_changed: tokio::sync::Notify,
     pub(crate) config: Config,
-    pub(crate) static_headers: HeaderMap,
+    /// upstream url without its multiplexing prefix
+    pub(crate) url: String,
+    /// replaced on config reload
+    pub(crate) static_headers: ArcSwap<HeaderMap>,
+    /// replaced on config reload
+    pub(crate) retry: ArcSwap<RetryPolicy>,
+    /// replaced on config reload
+    pub(crate) oauth: ArcSwap<Option<OAuthClient>>,
+    /// legacy SSE session, opened on first fallback
+    pub(crate) sse: tokio::sync::Mutex<Option<Arc<SseSession>>>,
+    pub(crate) sse_active: AtomicBool,
+    /// server-initiated messages waiting for the stdout writer
+    pub(crate) push_tx: Sender<Bytes>,
+    pub(crate) push_rx: Receiver<Bytes>,
+    /// multiplexed upstreams, empty for a single `--url`
+    pub(crate) upstreams: Vec<Upstream>,
+    /// set on multiplexed upstreams to route answers to server requests
+    pub(crate) server_requests: Option<ServerRequests>,
+    /// `upstreams` are an ordered failover list instead of multiplexed
+    pub(crate) failover: Option<Failover>,
+    pub(crate) circuit: CircuitBreaker,
+    /// list results, shared with multiplexed upstreams
+    pub(crate) list_cache: Arc<ListCache>,
+    /// reference point for `last_activity`
+    pub(crate) created: Instant,
+    /// milliseconds after `created` of the last upstream request
+    pub(crate) last_activity: AtomicU64,
+    /// MCP protocol version chosen by the server at initialize
+    pub(crate) protocol_version: ArcSwap<Option<String>>,
+    /// session persisted with `--session-file`
+    pub(crate) saved_session: std::sync::Mutex<Option<SessionState>>,
 }
 
 impl fmt::Debug for McpStreamClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("McpStreamClient")
             .field("session_id_present", &self.is_ready())
-            .field("config", &self.config)
+            .field("sse", &self.is_sse())
+            .field("circuit_open", &self.circuit.is_open())
             .field(
-                "authorization_header_present",
+                "upstreams",
                 &self
-                    .static_headers
-                    .contains_key(reqwest::header::AUTHORIZATION),
+                    .upstreams
+                    .iter()
+                    .map(|u| u.prefix.as_str())
+                    .collect::<Vec<_>>(),
             )
-            .finish()
+            .field("config", &self.config)
+            .field("authorization_header_present", &self.is_auth())
+            .finish_non_exhaustive()
     }
 }
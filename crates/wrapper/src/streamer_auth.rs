@@ -1,7 +1,31 @@
+use crate::config::Config;
 use crate::streamer::McpStreamClient;
+use reqwest::header::{AUTHORIZATION, HeaderName, HeaderValue};
 
 impl McpStreamClient {
+    /// Returns `true` if credentials are sent with every request
     pub fn is_auth(&self) -> bool {
         self.config.authorization_header.is_some()
+            || self.config.auth_token.is_some()
+            || self.oauth.load().is_some()
     }
 }
+
+/// builds the credential header from `--auth` or `--auth-token`
+/// # Errors
+/// * invalid header name or value
+pub(crate) fn auth_header(
+    config: &Config,
+) -> Result<Option<(HeaderName, HeaderValue)>, Box<dyn std::error::Error>> {
+    let (name, raw) = match (&config.authorization_header, &config.auth_token) {
+        (Some(auth), _) => (AUTHORIZATION, auth.clone()),
+        (None, Some(token)) => match &config.auth_header_name {
+            Some(name) => (HeaderName::from_bytes(name.as_bytes())?, token.clone()),
+            None => (AUTHORIZATION, format!("Bearer {token}")),
+        },
+        (None, None) => return Ok(None),
+    };
+    let mut value = HeaderValue::from_str(&raw)?;
+    value.set_sensitive(true);
+    Ok(Some((name, value)))
+}
@@ -0,0 +1,105 @@
+use crate::json_rpc_batch::{expects_response, is_response, join_batch, split_batch};
+use crate::json_rpc_validate::validate_message;
+use crate::rate_limit::RateLimiter;
+use crate::streamer::McpStreamClient;
+use crate::streamer_error::error_response;
+use bytes::Bytes;
+use futures::StreamExt;
+use jsonrpc_core::ErrorCode;
+use reqwest::Client;
+use std::sync::Arc;
+
+/// what one batch entry produced
+#[derive(Default)]
+struct EntryOutput {
+    /// responses to the entry, part of the batch response
+    responses: Vec<Bytes>,
+    /// server notifications and requests seen while answering the entry
+    messages: Vec<Bytes>,
+}
+
+impl McpStreamClient {
+    /// answers a JSON-RPC batch: up to `--concurrency` entries are posted at
+    /// once and their responses come back as one array in request order; server messages
+    /// that are not responses are written ahead of it
+    pub async fn post_batch(
+        self: &Arc<Self>,
+        client: &Client,
+        line: &[u8],
+        limiter: Option<&RateLimiter>,
+        validate: bool,
+    ) -> Vec<Bytes> {
+        let entries = match split_batch(line) {
+            Ok(entries) if entries.is_empty() => {
+                return vec![error_response(
+                    line,
+                    ErrorCode::InvalidRequest,
+                    "Invalid Request: empty batch",
+                )];
+            }
+            Ok(entries) => entries,
+            Err(e) => {
+                return vec![error_response(
+                    line,
+                    ErrorCode::ParseError,
+                    &format!("Parse error: {e}"),
+                )];
+            }
+        };
+
+        let outputs: Vec<EntryOutput> = futures::stream::iter(entries)
+            .map(|entry| self.post_entry(client, entry, limiter, validate))
+            .buffered(self.config.concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut lines = Vec::new();
+        let mut responses = Vec::new();
+        for output in outputs {
+            lines.extend(output.messages);
+            responses.extend(output.responses);
+        }
+        lines.extend(join_batch(&responses));
+        lines
+    }
+
+    async fn post_entry(
+        self: &Arc<Self>,
+        client: &Client,
+        entry: Bytes,
+        limiter: Option<&RateLimiter>,
+        validate: bool,
+    ) -> EntryOutput {
+        if let Some(Err(invalid)) = validate.then(|| validate_message(&entry)) {
+            return EntryOutput {
+                responses: vec![error_response(&entry, invalid.code, &invalid.message)],
+                messages: Vec::new(),
+            };
+        }
+        if let Some(limiter) = limiter {
+            limiter.acquire().await;
+        }
+        match self.post_guarded(client, entry.clone()).await {
+            Ok(res) => {
+                let (responses, messages) = res
+                    .messages()
+                    .into_iter()
+                    .partition(|message| is_response(message));
+                EntryOutput {
+                    responses,
+                    messages,
+                }
+            }
+            Err(e) if expects_response(&entry) => EntryOutput {
+                responses: vec![error_response(
+                    &entry,
+                    ErrorCode::InternalError,
+                    &e.to_string(),
+                )],
+                messages: Vec::new(),
+            },
+            // notifications get no answer, not even an error
+            Err(_) => EntryOutput::default(),
+        }
+    }
+}
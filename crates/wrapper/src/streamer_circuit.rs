@@ -0,0 +1,69 @@
+use crate::circuit::is_outage;
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use reqwest::Client;
+use std::sync::Arc;
+use tracing::debug;
+
+const PROBE: &[u8] = br#"{"jsonrpc":"2.0","id":"circuit-probe","method":"ping"}"#;
+
+impl McpStreamClient {
+    /// [`Self::post_with_retry`] behind the circuit breaker
+    /// # Errors
+    /// * upstream unavailable while the circuit is open
+    /// * any error from [`Self::post_with_retry`]
+    pub async fn post_guarded(
+        self: &Arc<Self>,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        if self.circuit.is_open() {
+            self.spawn_probe(client);
+            return Err(PostError::Other(format!(
+                "Upstream unavailable: {} consecutive failures, request not sent",
+                self.circuit.failures()
+            )));
+        }
+        let res = self.post_with_retry(client, payload).await;
+        match &res {
+            Ok(_) => self.circuit.record_success(),
+            Err(e) if is_outage(e) => {
+                if self.circuit.record_failure() {
+                    self.spawn_probe(client);
+                }
+            }
+            Err(_) => {}
+        }
+        res
+    }
+
+    /// pings the upstream until it answers, then closes the circuit;
+    /// stops once the client is dropped, e.g. when a `--listen` session ends
+    fn spawn_probe(self: &Arc<Self>, client: &Client) {
+        if !self.circuit.start_probe() {
+            return;
+        }
+        let weak = Arc::downgrade(self);
+        let interval = self.circuit.probe_interval;
+        let client = client.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(mcp) = weak.upgrade() else {
+                    debug!("Client dropped, circuit probe stopped");
+                    return;
+                };
+                match mcp.stream_post(&client, Bytes::from_static(PROBE)).await {
+                    Err(e) if is_outage(&e) => debug!("Circuit probe failed: {e}"),
+                    _ => {
+                        mcp.circuit.record_success();
+                        mcp.circuit.end_probe();
+                        return;
+                    }
+                }
+            }
+        });
+    }
+}
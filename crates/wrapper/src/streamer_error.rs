@@ -1,4 +1,5 @@
 use crate::json_rpc_id_fast::parse_id_fast;
+use crate::redact::redact;
 use bytes::Bytes;
 use flume::Sender;
 use jsonrpc_core::{Error, ErrorCode, Failure, Version, serde_json};
@@ -14,11 +15,19 @@ pub async fn mcp_error(
     error_msg: &str,
     tx: &Sender<Bytes>,
 ) {
+    let json_msg = error_response(json_str, ErrorCode::InternalError, error_msg);
+    if let Err(e) = tx.send_async(json_msg).await {
+        error!("Worker {worker_id}: failed to send JSON-RPC response: {e}");
+    }
+}
+/// creates JSON-RPC error response for the request in `json_str`
+#[must_use]
+pub fn error_response(json_str: &[u8], code: ErrorCode, error_msg: &str) -> Bytes {
     let id = parse_id_fast(json_str);
     tracing::debug!("Json rpc id:{id:?}");
     let error_obj = Error {
-        code: ErrorCode::InternalError,
-        message: error_msg.to_string(),
+        code,
+        message: redact(error_msg).into_owned(),
         data: None,
     };
 
@@ -32,10 +41,17 @@ pub async fn mcp_error(
         Ok(msg) => msg,
         Err(e) => rpc_error(&response, &e),
     };
-
-    if let Err(e) = tx.send_async(Bytes::from(json_msg)).await {
-        error!("Worker {worker_id}: failed to send JSON-RPC response: {e}");
-    }
+    Bytes::from(json_msg)
+}
+/// creates error message
+#[must_use]
+pub fn line_limit_error(max: usize) -> String {
+    format!("Request exceeds --max-line-bytes limit of {max} bytes")
+}
+/// creates error message
+#[must_use]
+pub fn response_limit_error(max: usize) -> String {
+    format!("Upstream response exceeds --max-response-bytes limit of {max} bytes")
 }
 /// creates error message
 #[must_use]
@@ -54,6 +70,19 @@ pub fn invalid_error(path: &Path, e: &reqwest::Error) -> String {
 }
 /// creates error message
 #[must_use]
+pub fn empty_bundle_error(path: &Path) -> String {
+    format!(
+        "Invalid PEM in cert file {}: no certificates found",
+        path.display()
+    )
+}
+/// creates error message
+#[must_use]
+pub fn identity_error(path: &Path, e: &reqwest::Error) -> String {
+    format!("Invalid client identity in {}: {}", path.display(), e)
+}
+/// creates error message
+#[must_use]
 pub fn read_error(path: &Path, e: &std::io::Error) -> String {
     format!("Failed to read cert file {}: {}", path.display(), e)
 }
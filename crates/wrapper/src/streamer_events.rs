@@ -0,0 +1,122 @@
+use crate::sse_events::EventReader;
+use crate::streamer::McpStreamClient;
+use crate::streamer_sse::EVENT_STREAM_TIMEOUT;
+use reqwest::{Client, StatusCode};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+const MESSAGE_EVENT: &str = "message";
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// why an event stream connection ended
+enum StreamEnd {
+    /// reconnect after a pause
+    Retry,
+    /// the stream was healthy, reconnect after the shortest pause
+    Reconnect,
+    /// server does not offer a stream or the wrapper is shutting down
+    Stop,
+}
+
+impl McpStreamClient {
+    /// keeps the streamable HTTP GET stream open so server-initiated
+    /// messages reach stdout; one stream per multiplexed upstream
+    pub fn spawn_event_stream(self: &Arc<Self>, client: Client) -> Vec<JoinHandle<()>> {
+        if self.is_mux() {
+            return self
+                .upstreams
+                .iter()
+                .flat_map(|u| u.client.spawn_event_stream(client.clone()))
+                .collect();
+        }
+        let mcp = Arc::clone(self);
+        vec![tokio::spawn(async move {
+            let mut delay = RECONNECT_MIN;
+            loop {
+                mcp.wait_for_session().await;
+                match mcp.read_event_stream(&client).await {
+                    StreamEnd::Stop => break,
+                    StreamEnd::Reconnect => {
+                        delay = RECONNECT_MIN;
+                        tokio::time::sleep(delay).await;
+                    }
+                    StreamEnd::Retry => {
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(RECONNECT_MAX);
+                    }
+                }
+            }
+            debug!("Event stream task finished");
+        })]
+    }
+
+    /// the GET stream belongs to a session, so wait for initialize
+    async fn wait_for_session(&self) {
+        let changed = self.session_changed.notified();
+        tokio::pin!(changed);
+        changed.as_mut().enable();
+        if !self.is_ready() {
+            changed.await;
+        }
+    }
+
+    async fn read_event_stream(&self, client: &Client) -> StreamEnd {
+        if self.is_sse() {
+            // legacy transport already streams everything
+            return StreamEnd::Stop;
+        }
+        let request = match self
+            .with_headers(client, client.get(&self.url).timeout(EVENT_STREAM_TIMEOUT))
+            .await
+        {
+            Ok(request) => request,
+            Err(e) => {
+                warn!("Event stream: {e}");
+                return StreamEnd::Retry;
+            }
+        };
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Event stream connect failed: {e}");
+                return StreamEnd::Retry;
+            }
+        };
+        let status = response.status();
+        if status == StatusCode::METHOD_NOT_ALLOWED {
+            info!("Server offers no event stream, server-initiated messages disabled");
+            return StreamEnd::Stop;
+        }
+        if !status.is_success() {
+            warn!("Event stream rejected with {status}");
+            return StreamEnd::Retry;
+        }
+        debug!("Event stream open");
+
+        let mut events = EventReader::new(response).with_limit(self.config.max_response_bytes);
+        loop {
+            match events.next_event().await {
+                Ok(Some(event)) if event.event == MESSAGE_EVENT => {
+                    if let Some(requests) = &self.server_requests {
+                        requests.note(&event.data);
+                    }
+                    if self.push_tx.send_async(event.data).await.is_err() {
+                        return StreamEnd::Stop;
+                    }
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    debug!("Event stream closed by server");
+                    return StreamEnd::Reconnect;
+                }
+                Err(e) => {
+                    error!("Event stream {e}");
+                    return StreamEnd::Retry;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::circuit::is_outage;
+use crate::config::sanitize_url_for_debug;
+use crate::json_rpc_id_fast::parse_field_fast;
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use jsonrpc_core::Id;
+use reqwest::Client;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const INITIALIZED: &[u8] = br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+const LIST_KINDS: [&str; 3] = ["tools", "prompts", "resources"];
+
+impl McpStreamClient {
+    /// Returns `true` when several `--url` values form a failover list
+    pub fn is_failover(&self) -> bool {
+        self.failover.is_some()
+    }
+
+    /// posts to the active upstream; repeated outages move the session to
+    /// the next url and the message is sent there once more
+    pub(crate) async fn failover_post(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let Some(failover) = &self.failover else {
+            return self.post_single(client, payload).await;
+        };
+        let is_initialize =
+            matches!(parse_field_fast(&payload, "method"), Id::Str(m) if m == "initialize");
+        if is_initialize {
+            failover.remember_initialize(&payload);
+        }
+
+        let active = failover.active();
+        match self.upstreams[active]
+            .client
+            .post_single(client, payload.clone())
+            .await
+        {
+            Ok(res) => {
+                failover.record_success();
+                Ok(res)
+            }
+            Err(e) if is_outage(&e) && failover.record_failure() => {
+                warn!(
+                    "Upstream {} failing: {e}",
+                    self.upstreams[active].client.url
+                );
+                let next = self.fail_over(client, active, !is_initialize).await?;
+                self.upstreams[next]
+                    .client
+                    .post_single(client, payload)
+                    .await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// makes the next reachable upstream active, re-initializing the session there
+    async fn fail_over(
+        &self,
+        client: &Client,
+        from: usize,
+        reinitialize: bool,
+    ) -> Result<usize, PostError> {
+        let Some(failover) = &self.failover else {
+            return Ok(from);
+        };
+        let _switching = failover.switching.lock().await;
+        if failover.active() != from {
+            // another worker switched while this one waited
+            return Ok(failover.active());
+        }
+        let initialize = failover.initialize().filter(|_| reinitialize);
+        let count = self.upstreams.len();
+        for step in 1..count {
+            let next = (from + step) % count;
+            let candidate = &self.upstreams[next].client;
+            if let Some(initialize) = &initialize {
+                if let Err(e) = candidate.post_single(client, initialize.clone()).await {
+                    warn!("Failover to {} failed: {e}", candidate.url);
+                    continue;
+                }
+                if let Err(e) = candidate
+                    .post_single(client, Bytes::from_static(INITIALIZED))
+                    .await
+                {
+                    warn!(
+                        "Failover to {}: initialized notification failed: {e}",
+                        candidate.url
+                    );
+                }
+            }
+            failover.set_active(next);
+            self.upstreams[from].client.session_id.store(Arc::new(None));
+            info!(
+                "Failed over from {} to {}",
+                self.upstreams[from].client.url, candidate.url
+            );
+            self.announce_failover(from, next).await;
+            return Ok(next);
+        }
+        Err(PostError::Other(format!(
+            "All {count} upstreams unavailable, no failover possible"
+        )))
+    }
+
+    /// drops cached lists of the old server and tells the client about the switch
+    async fn announce_failover(&self, from: usize, to: usize) {
+        for kind in LIST_KINDS {
+            let changed =
+                format!(r#"{{"jsonrpc":"2.0","method":"notifications/{kind}/list_changed"}}"#);
+            self.list_cache.observe(changed.as_bytes());
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": "warning",
+                "logger": "mcp-stdio-wrapper",
+                "data": {
+                    "event": "failover",
+                    "from": sanitize_url_for_debug(&self.upstreams[from].client.url),
+                    "to": sanitize_url_for_debug(&self.upstreams[to].client.url),
+                },
+            },
+        });
+        if let Err(e) = self
+            .push_tx
+            .send_async(Bytes::from(notification.to_string()))
+            .await
+        {
+            warn!("Failover notification not delivered: {e}");
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use reqwest::header::{HeaderName, HeaderValue};
+
+/// splits a `Name: value` string into a validated header pair
+/// # Errors
+/// * missing separator, invalid header name or value
+pub fn parse_header(raw: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let Some((name, value)) = raw.split_once(':') else {
+        // the value may be a mistyped secret, so it is not echoed
+        return Err("Invalid header: expected 'Name: value'".to_string());
+    };
+    let name = HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|e| format!("Invalid header name '{}': {e}", name.trim()))?;
+    let mut value = HeaderValue::from_str(value.trim())
+        .map_err(|e| format!("Invalid value for header '{name}': {e}"))?;
+    value.set_sensitive(true);
+    Ok((name, value))
+}
+
+/// clap value parser for `--header`
+/// # Errors
+/// * see [`parse_header`]
+pub fn header_arg(raw: &str) -> Result<String, String> {
+    parse_header(raw).map(|_| raw.to_string())
+}
+
+/// header name of a `Name: value` string, safe to log
+#[must_use]
+pub fn header_name(raw: &str) -> &str {
+    raw.split_once(':').map_or(raw, |(name, _)| name).trim()
+}
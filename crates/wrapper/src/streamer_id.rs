@@ -15,6 +15,7 @@ impl McpStreamClient {
                 .compare_and_swap(&*current_guard, Arc::clone(&new_arc));
 
             if Arc::ptr_eq(&*prev_guard, &*current_guard) {
+                self.session_changed.notify_waiters();
                 return; // Success
             }
             current_guard = prev_guard;
@@ -0,0 +1,59 @@
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use reqwest::Client;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+const PING: &[u8] = br#"{"jsonrpc":"2.0","id":"keepalive","method":"ping"}"#;
+
+impl McpStreamClient {
+    /// notes upstream traffic, postponing the next keep-alive ping
+    pub(crate) fn touch(&self) {
+        let elapsed = u64::try_from(self.created.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.last_activity.store(elapsed, Ordering::Relaxed);
+    }
+
+    /// time since the last request to the upstream
+    #[must_use]
+    pub fn idle_for(&self) -> Duration {
+        let last = Duration::from_millis(self.last_activity.load(Ordering::Relaxed));
+        self.created.elapsed().saturating_sub(last)
+    }
+
+    /// pings the upstream after `idle` without traffic so the session
+    /// and intermediate proxies do not expire it; one task per upstream
+    pub fn spawn_keepalive(
+        self: &Arc<Self>,
+        client: Client,
+        idle: Duration,
+    ) -> Vec<JoinHandle<()>> {
+        if self.is_mux() {
+            return self
+                .upstreams
+                .iter()
+                .flat_map(|u| u.client.spawn_keepalive(client.clone(), idle))
+                .collect();
+        }
+        let mcp = Arc::clone(self);
+        vec![tokio::spawn(async move {
+            loop {
+                let idle_for = mcp.idle_for();
+                if idle_for < idle {
+                    tokio::time::sleep(idle.saturating_sub(idle_for)).await;
+                    continue;
+                }
+                if mcp.is_ready() {
+                    debug!("Idle for {idle_for:?}, sending keep-alive ping");
+                    if let Err(e) = mcp.post_single(&client, Bytes::from_static(PING)).await {
+                        warn!("Keep-alive ping failed: {e}");
+                    }
+                }
+                // no session yet or ping failed: try again after another period
+                mcp.touch();
+            }
+        })]
+    }
+}
@@ -0,0 +1,279 @@
+use crate::json_rpc_id_fast::{parse_field_fast, parse_id_fast};
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use futures::future::join_all;
+use jsonrpc_core::Id;
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+/// separates the upstream prefix from the tool name
+pub const PREFIX_SEPARATOR: &str = "__";
+/// upper bound on tools/list pages fetched from one upstream
+const MAX_PAGES: usize = 100;
+/// unanswered server-initiated requests remembered for routing
+const MAX_SERVER_REQUESTS: usize = 1024;
+
+/// one upstream server behind the multiplexer
+pub(crate) struct Upstream {
+    pub(crate) prefix: String,
+    pub(crate) client: Arc<McpStreamClient>,
+}
+
+/// which upstream sent each server-initiated request, so the client's
+/// answer goes back to it; one map shared by all multiplexed upstreams
+#[derive(Clone, Debug)]
+pub(crate) struct ServerRequests {
+    /// index of the upstream recording through this handle
+    upstream: usize,
+    routes: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl ServerRequests {
+    pub(crate) fn new(upstream: usize, routes: Arc<Mutex<HashMap<String, usize>>>) -> Self {
+        Self { upstream, routes }
+    }
+
+    /// remembers `message` when it is a request from the server
+    pub(crate) fn note(&self, message: &[u8]) {
+        if parse_field_fast(message, "method") == Id::Null {
+            return;
+        }
+        let id = parse_id_fast(message);
+        if id == Id::Null {
+            return;
+        }
+        let Ok(key) = serde_json::to_string(&id) else {
+            return;
+        };
+        let Ok(mut routes) = self.routes.lock() else {
+            return;
+        };
+        if routes.len() >= MAX_SERVER_REQUESTS {
+            debug!("Too many unanswered server requests, {key} goes to the first upstream");
+            return;
+        }
+        routes.insert(key, self.upstream);
+    }
+
+    /// upstream that sent request `id`, forgotten once asked
+    fn take(&self, id: &Value) -> Option<usize> {
+        self.routes.lock().ok()?.remove(&id.to_string())
+    }
+}
+
+/// splits an optional `name=` prefix from a `--url` value
+#[must_use]
+pub fn split_url_prefix(raw: &str) -> (Option<&str>, &str) {
+    match (raw.find('='), raw.find("://")) {
+        (Some(eq), Some(scheme)) if eq < scheme => (Some(&raw[..eq]), &raw[eq + 1..]),
+        _ => (None, raw),
+    }
+}
+
+/// tool prefix of the upstream at `index`, `s1`, `s2`, ... unless named
+#[must_use]
+pub fn upstream_prefix(raw: &str, index: usize) -> String {
+    split_url_prefix(raw)
+        .0
+        .map_or_else(|| format!("s{}", index + 1), str::to_string)
+}
+
+impl McpStreamClient {
+    /// Returns `true` when several upstreams are behind this client,
+    /// multiplexed or as a failover list
+    pub fn is_mux(&self) -> bool {
+        !self.upstreams.is_empty()
+    }
+
+    /// routes a message to the owning upstream, or to all of them
+    pub(crate) async fn mux_post(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let primary = &self.upstreams[0];
+        let Ok(mut message) = serde_json::from_slice::<Value>(&payload) else {
+            return primary.post(client, payload).await;
+        };
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        match method.as_deref() {
+            Some("initialize" | "notifications/initialized") => {
+                self.mux_broadcast(client, payload).await
+            }
+            Some("tools/list") => self.mux_tools_list(client, &message).await,
+            Some("tools/call") => {
+                let upstream = self.route_tool(&mut message)?;
+                let payload = serde_json::to_vec(&message).map_err(|e| e.to_string())?;
+                upstream.post(client, Bytes::from(payload)).await
+            }
+            // prompts, resources and everything else belong to the first url
+            Some(_) => primary.post(client, payload).await,
+            // answer to a server-initiated request
+            None => {
+                let upstream = message
+                    .get("id")
+                    .zip(primary.client.server_requests.as_ref())
+                    .and_then(|(id, requests)| requests.take(id))
+                    .and_then(|i| self.upstreams.get(i))
+                    .unwrap_or(primary);
+                upstream.post(client, payload).await
+            }
+        }
+    }
+
+    /// sends to every upstream, answers with the first successful response
+    async fn mux_broadcast(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let results = join_all(
+            self.upstreams
+                .iter()
+                .map(|u| u.post(client, payload.clone())),
+        )
+        .await;
+
+        let mut first = None;
+        let mut errors = Vec::new();
+        for (upstream, result) in self.upstreams.iter().zip(results) {
+            match result {
+                Ok(res) => {
+                    if first.is_none() {
+                        first = Some(res);
+                    }
+                }
+                Err(e) => {
+                    warn!("Upstream {}: {e}", upstream.prefix);
+                    errors.push(format!("{}: {e}", upstream.prefix));
+                }
+            }
+        }
+        first.ok_or_else(|| PostError::Other(errors.join("; ")))
+    }
+
+    /// merges the tools of all upstreams under their prefixes
+    async fn mux_tools_list(
+        &self,
+        client: &Client,
+        message: &Value,
+    ) -> Result<PostResult, PostError> {
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        let results = join_all(self.upstreams.iter().map(|u| u.collect_tools(client, &id))).await;
+
+        let mut tools = Vec::new();
+        let mut errors = Vec::new();
+        for (upstream, result) in self.upstreams.iter().zip(results) {
+            match result {
+                Ok(list) => tools.extend(
+                    list.into_iter()
+                        .map(|tool| prefix_tool(&upstream.prefix, tool)),
+                ),
+                Err(e) => {
+                    warn!("Upstream {} tools/list failed: {e}", upstream.prefix);
+                    errors.push(format!("{}: {e}", upstream.prefix));
+                }
+            }
+        }
+        if tools.is_empty() && !errors.is_empty() {
+            return Err(PostError::Other(errors.join("; ")));
+        }
+
+        let response = json!({"jsonrpc": "2.0", "id": id, "result": {"tools": tools}});
+        Ok(PostResult {
+            out: vec![Bytes::from(response.to_string())],
+            sse: false,
+        })
+    }
+
+    /// strips the prefix from the tool name and returns its upstream
+    fn route_tool(&self, message: &mut Value) -> Result<&Upstream, String> {
+        let name = message
+            .pointer("/params/name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or("tools/call without tool name")?;
+        let upstream = name
+            .split_once(PREFIX_SEPARATOR)
+            .and_then(|(prefix, tool)| {
+                let upstream = self.upstreams.iter().find(|u| u.prefix == prefix)?;
+                Some((upstream, tool))
+            })
+            .map(|(upstream, tool)| {
+                message["params"]["name"] = Value::from(tool);
+                upstream
+            })
+            .ok_or_else(|| format!("Unknown tool '{name}'"))?;
+        Ok(upstream)
+    }
+}
+
+impl Upstream {
+    /// posts to this upstream and remembers the requests it sends back
+    async fn post(&self, client: &Client, payload: Bytes) -> Result<PostResult, PostError> {
+        let res = self.client.post_single(client, payload).await?;
+        if let Some(requests) = &self.client.server_requests {
+            for message in res.messages() {
+                requests.note(&message);
+            }
+        }
+        Ok(res)
+    }
+
+    /// fetches all tools/list pages of this upstream
+    async fn collect_tools(&self, client: &Client, id: &Value) -> Result<Vec<Value>, PostError> {
+        let mut tools = Vec::new();
+        let mut cursor = None;
+        for _ in 0..MAX_PAGES {
+            let mut request = json!({"jsonrpc": "2.0", "id": id, "method": "tools/list"});
+            if let Some(cursor) = cursor.take() {
+                request["params"] = json!({ "cursor": cursor });
+            }
+            let res = self.post(client, Bytes::from(request.to_string())).await?;
+            let mut result = find_result(&res, id)?;
+            if let Some(Value::Array(page)) = result.get_mut("tools").map(Value::take) {
+                tools.extend(page);
+            }
+            match result.get_mut("nextCursor").map(Value::take) {
+                Some(next) if !next.is_null() => cursor = Some(next),
+                _ => return Ok(tools),
+            }
+        }
+        Ok(tools)
+    }
+}
+
+/// extracts the result of the response matching `id`
+fn find_result(res: &PostResult, id: &Value) -> Result<Value, String> {
+    for line in res.messages() {
+        let Ok(mut message) = serde_json::from_slice::<Value>(&line) else {
+            continue;
+        };
+        if message.get("id") != Some(id) {
+            continue;
+        }
+        if let Some(error) = message.get("error") {
+            return Err(format!("Upstream error: {error}"));
+        }
+        if let Some(result) = message.get_mut("result") {
+            return Ok(result.take());
+        }
+    }
+    Err("Missing tools/list response".to_string())
+}
+
+fn prefix_tool(prefix: &str, mut tool: Value) -> Value {
+    if let Some(name) = tool.get("name").and_then(Value::as_str) {
+        let prefixed = format!("{prefix}{PREFIX_SEPARATOR}{name}");
+        tool["name"] = Value::String(prefixed);
+    }
+    tool
+}
@@ -1,34 +1,137 @@
+use crate::circuit::CircuitBreaker;
 use crate::config::Config;
+use crate::failover::Failover;
+use crate::list_cache::ListCache;
+use crate::oauth::OAuthClient;
+use crate::protocol::protocol_version;
+use crate::retry::RetryPolicy;
+use crate::session_store::load_session;
 use crate::streamer::McpStreamClient;
+use crate::streamer_auth::auth_header;
+use crate::streamer_headers::parse_header;
+use crate::streamer_mux::{ServerRequests, Upstream, split_url_prefix, upstream_prefix};
 use arc_swap::ArcSwap;
-use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::time::{Duration, Instant};
 
 const ACCEPT_VALUES: &str = "application/json, application/x-ndjson, text/event-stream";
+const PUSH_CAPACITY: usize = 256;
 
 impl McpStreamClient {
     #[allow(unused)]
     /// Initialize the client with standard MCP headers
     /// # Errors
-    /// * invalid auth header
+    /// * invalid auth or custom header
     /// # Panics
     /// * wrong or missing tls certificate
     pub fn try_new(config: Config) -> Result<Self, Box<dyn std::error::Error>> {
         // Build static headers once during initialization
-        let mut static_headers = HeaderMap::new();
-        static_headers.insert(ACCEPT, HeaderValue::from_static(ACCEPT_VALUES));
-        let cont_type = HeaderValue::from_str(&config.mcp_content_type)?;
-        static_headers.insert(CONTENT_TYPE, cont_type);
-
-        // Add authorization header if configured
-        if let Some(auth) = config.authorization_header.as_ref() {
-            let auth_header = HeaderValue::from_str(auth)?;
-            static_headers.insert(AUTHORIZATION, auth_header);
+        let static_headers = build_headers(&config)?;
+
+        let (push_tx, push_rx) = flume::bounded(PUSH_CAPACITY);
+        let list_cache = Arc::new(ListCache::new(Duration::from_secs(config.list_cache_ttl)));
+
+        // several urls: this client only routes, each upstream gets its own session
+        let mut upstreams: Vec<Upstream> = Vec::new();
+        let routes = Arc::default();
+        if config.mcp_server_url.len() > 1 {
+            for (i, raw) in config.mcp_server_url.iter().enumerate() {
+                let prefix = upstream_prefix(raw, i);
+                if upstreams.iter().any(|u| u.prefix == prefix) {
+                    return Err(format!("Duplicate upstream prefix '{prefix}'").into());
+                }
+                let mut upstream_config = config.clone();
+                upstream_config.mcp_server_url = vec![split_url_prefix(raw).1.to_string()];
+                upstream_config.session_file = config.session_file.as_ref().map(|path| {
+                    let mut name = path.clone().into_os_string();
+                    name.push(format!(".{prefix}"));
+                    PathBuf::from(name)
+                });
+                let mut client = Self::try_new(upstream_config)?;
+                client.push_tx = push_tx.clone();
+                client.push_rx = push_rx.clone();
+                client.list_cache = Arc::clone(&list_cache);
+                if !config.failover {
+                    client.server_requests = Some(ServerRequests::new(i, Arc::clone(&routes)));
+                }
+                upstreams.push(Upstream {
+                    prefix,
+                    client: Arc::new(client),
+                });
+            }
         }
+        let url = config
+            .mcp_server_url
+            .first()
+            .map(|raw| split_url_prefix(raw).1.to_string())
+            .unwrap_or_default();
+
+        // resume only a session negotiated with the same upstream
+        let saved_session = config
+            .session_file
+            .as_deref()
+            .filter(|_| upstreams.is_empty())
+            .and_then(load_session)
+            .filter(|state| state.url == url);
+
+        let failover = (config.failover && !upstreams.is_empty())
+            .then(|| Failover::new(config.failover_after));
 
         Ok(Self {
-            session_id: ArcSwap::from_pointee(None),
+            url,
+            upstreams,
+            failover,
+            server_requests: None,
+            session_id: ArcSwap::from_pointee(
+                saved_session.as_ref().map(|state| state.session_id.clone()),
+            ),
+            session_changed: tokio::sync::Notify::new(),
+            created: Instant::now(),
+            last_activity: AtomicU64::new(0),
+            protocol_version: ArcSwap::from_pointee(
+                saved_session.as_ref().and_then(|state| {
+                    protocol_version(&state.initialize_result).map(str::to_string)
+                }),
+            ),
+            saved_session: std::sync::Mutex::new(saved_session),
+            list_cache,
+            oauth: ArcSwap::from_pointee(OAuthClient::from_config(&config)),
+            circuit: CircuitBreaker::new(
+                config.circuit_threshold,
+                Duration::from_millis(config.circuit_probe_ms),
+            ),
+            sse: tokio::sync::Mutex::new(None),
+            sse_active: AtomicBool::new(false),
+            push_tx,
+            push_rx,
+            static_headers: ArcSwap::from_pointee(static_headers),
+            retry: ArcSwap::from_pointee(RetryPolicy::from_config(&config)),
             config,
-            static_headers,
         })
     }
 }
+
+/// headers sent with every request: content negotiation, auth and `--header` values
+/// # Errors
+/// * invalid auth or custom header
+pub(crate) fn build_headers(config: &Config) -> Result<HeaderMap, Box<dyn std::error::Error>> {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, HeaderValue::from_static(ACCEPT_VALUES));
+    let cont_type = HeaderValue::from_str(&config.mcp_content_type)?;
+    headers.insert(CONTENT_TYPE, cont_type);
+
+    // Add authorization header if configured
+    if let Some((name, value)) = auth_header(config)? {
+        headers.insert(name, value);
+    }
+
+    // Custom headers override built-in ones with the same name
+    for raw in &config.headers {
+        let (name, value) = parse_header(raw)?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
@@ -1,10 +1,14 @@
+use crate::post_error::PostError;
 use crate::post_result::PostResult;
 use crate::streamer::McpStreamClient;
+use crate::streamer_error::response_limit_error;
 use crate::streamer_lines::extract_lines;
+use crate::streamer_protocol::downgraded_initialize;
 use bytes::{Bytes, BytesMut};
 use futures::StreamExt;
-use reqwest::Client;
 use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Response, StatusCode};
+use std::time::Duration;
 use tracing::{debug, error};
 
 impl McpStreamClient {
@@ -12,47 +16,177 @@ impl McpStreamClient {
     /// Performs a streaming POST request and processes the response into lines of bytes.
     /// # Errors
     /// This function will return an error if the request or stream processing fails.
-    pub async fn stream_post(&self, client: &Client, payload: Bytes) -> Result<PostResult, String> {
-        let response = self.prepare_and_send_request(client, payload).await?;
+    pub async fn stream_post(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let key = self.list_cache.key(&payload);
+        if let Some(key) = &key
+            && let Some(res) = self.list_cache.get(key, &payload)
+        {
+            return Ok(res);
+        }
+
+        let res = if self.is_failover() {
+            self.failover_post(client, payload).await?
+        } else if self.is_mux() {
+            self.mux_post(client, payload).await?
+        } else {
+            self.post_single(client, payload).await?
+        };
+
+        // notifications may arrive in the response stream as well
+        for message in res.messages() {
+            self.list_cache.observe(&message);
+        }
+        if let Some(key) = key {
+            self.list_cache.put(key, &res);
+        }
+        Ok(res)
+    }
+
+    /// [`Self::stream_post`] bounded by `--request-timeout`
+    /// # Errors
+    /// * [`PostError::Timeout`] when the upstream does not answer in time
+    /// * any error from [`Self::stream_post`]
+    pub async fn stream_post_timeout(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let Some(secs) = self.config.request_timeout.filter(|s| *s > 0) else {
+            return self.stream_post(client, payload).await;
+        };
+        let limit = Duration::from_secs(secs);
+        tokio::time::timeout(limit, self.stream_post(client, payload))
+            .await
+            .unwrap_or(Err(PostError::Timeout(limit)))
+    }
+
+    /// streaming POST to this client's own upstream
+    pub(crate) async fn post_single(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        if self.is_sse() {
+            return self.sse_post(client, payload).await;
+        }
+        if let Some(res) = self.resumed_initialize(&payload) {
+            return Ok(res);
+        }
+
+        let mut response = self
+            .prepare_and_send_request(client, payload.clone())
+            .await?;
+        let oauth = self.oauth.load_full();
+        if response.status() == StatusCode::UNAUTHORIZED
+            && let Some(oauth) = oauth.as_ref()
+        {
+            // token revoked or expired early: refresh and retry once
+            debug!("Upstream rejected OAuth token, refreshing");
+            oauth.invalidate().await;
+            response = self
+                .prepare_and_send_request(client, payload.clone())
+                .await?;
+        }
+        if self.session_rejected(response.status()) {
+            self.reinitialize(client, &payload).await?;
+            response = self
+                .prepare_and_send_request(client, payload.clone())
+                .await?;
+        }
         let status = response.status();
 
+        // servers without streamable HTTP reject the POST before any session exists
+        if matches!(
+            status,
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+        ) && !self.config.no_sse_fallback
+            && !self.is_ready()
+        {
+            debug!("Streamable HTTP rejected with {status}, falling back to SSE");
+            return self.sse_post(client, payload).await;
+        }
+
         if !status.is_success() {
-            let err_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Could not read error body".to_string());
+            let err_text = read_error_body(response, self.config.max_response_bytes).await?;
 
+            if let Some(retry) = downgraded_initialize(&payload, [err_text.as_bytes()]) {
+                return Box::pin(self.post_single(client, retry)).await;
+            }
             error!("Server returned error {}: {}", status, err_text);
-            return Err(format!("Server error {status}: {err_text}"));
+            return Err(PostError::Status {
+                status,
+                body: err_text,
+            });
         }
 
-        let sse = response
-            .headers()
-            .get(CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .is_some_and(|s| s.contains("text/event-stream"));
-
         self.process_session_id(&response);
+        let res = read_body(response, self.config.max_response_bytes).await?;
+        let messages = res.messages();
+        if let Some(retry) = downgraded_initialize(&payload, messages.iter().map(|m| &m[..])) {
+            return Box::pin(self.post_single(client, retry)).await;
+        }
+        self.observe_initialize(&payload, &res);
+        self.remember_initialize(&payload, &res);
+        Ok(res)
+    }
+}
 
-        let mut out = Vec::new();
-        let mut buffer = BytesMut::new();
-        let mut stream = response.bytes_stream();
+/// reads an error response body as text through [`read_body`], so the same
+/// `max` applies
+/// # Errors
+/// * body larger than `max`
+pub(crate) async fn read_error_body(response: Response, max: usize) -> Result<String, PostError> {
+    match read_body(response, max).await {
+        Ok(body) => Ok(body
+            .out
+            .iter()
+            .map(|line| String::from_utf8_lossy(line))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        Err(PostError::Transport(_)) => Ok("Could not read error body".to_string()),
+        Err(e) => Err(e),
+    }
+}
+
+/// streams a successful response body into lines, at most `max` bytes (0 = unlimited)
+/// # Errors
+/// * stream interrupted
+/// * body larger than `max`
+pub(crate) async fn read_body(response: Response, max: usize) -> Result<PostResult, PostError> {
+    let sse = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|s| s.contains("text/event-stream"));
 
-        while let Some(item) = stream.next().await {
-            match item {
-                Ok(chunk) => {
-                    buffer.extend_from_slice(&chunk);
-                    extract_lines(&mut buffer, &mut out);
+    let mut out = Vec::new();
+    let mut buffer = BytesMut::new();
+    let mut stream = response.bytes_stream();
+    let mut total = 0;
+
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(chunk) => {
+                total += chunk.len();
+                if max != 0 && total > max {
+                    error!("Upstream response larger than {max} bytes, dropped");
+                    return Err(PostError::Other(response_limit_error(max)));
                 }
-                Err(e) => return Err(format!("Stream interrupted: {e}")),
+                buffer.extend_from_slice(&chunk);
+                extract_lines(&mut buffer, &mut out);
             }
+            Err(e) => return Err(PostError::Transport(format!("Stream interrupted: {e}"))),
         }
+    }
 
-        if !buffer.is_empty() {
-            out.push(buffer.freeze());
-        }
-        debug!("Received lines: {out:?}");
-
-        Ok(PostResult { out, sse })
+    if !buffer.is_empty() {
+        out.push(buffer.freeze());
     }
+    debug!("Received lines: {out:?}");
+
+    Ok(PostResult { out, sse })
 }
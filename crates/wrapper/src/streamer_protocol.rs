@@ -0,0 +1,69 @@
+use crate::post_result::PostResult;
+use crate::protocol::{downgrade_initialize, negotiate, protocol_version, supported_versions};
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+const INITIALIZE: &str = "initialize";
+
+/// the request as JSON when it is an initialize
+fn initialize_request(payload: &[u8]) -> Option<Value> {
+    serde_json::from_slice::<Value>(payload)
+        .ok()
+        .filter(|request| request.get("method").and_then(Value::as_str) == Some(INITIALIZE))
+}
+
+/// initialize to re-send when the server refused the requested version
+/// but listed one both sides support
+pub(crate) fn downgraded_initialize<'a>(
+    payload: &[u8],
+    messages: impl IntoIterator<Item = &'a [u8]>,
+) -> Option<Bytes> {
+    let request = initialize_request(payload)?;
+    let requested = request.get("params").and_then(protocol_version)?;
+    let supported = messages
+        .into_iter()
+        .filter_map(|message| serde_json::from_slice::<Value>(message).ok())
+        .find_map(|message| supported_versions(&message))?;
+    let version = negotiate(requested, &supported).filter(|v| v.as_str() < requested)?;
+    info!("Upstream does not support protocol {requested}, retrying initialize with {version}");
+    Some(Bytes::from(
+        downgrade_initialize(&request, &version).to_string(),
+    ))
+}
+
+impl McpStreamClient {
+    /// protocol version negotiated by the last initialize
+    #[must_use]
+    pub fn protocol_version(&self) -> Option<String> {
+        (**self.protocol_version.load()).clone()
+    }
+
+    pub(crate) fn set_protocol_version(&self, version: Option<&str>) {
+        debug!("Negotiated protocol version {version:?}");
+        self.protocol_version
+            .store(Arc::new(version.map(str::to_string)));
+    }
+
+    /// remembers the version the server chose in its initialize result
+    pub(crate) fn observe_initialize(&self, payload: &[u8], res: &PostResult) {
+        if initialize_request(payload).is_none() {
+            return;
+        }
+        let version = res
+            .messages()
+            .iter()
+            .filter_map(|message| serde_json::from_slice::<Value>(message).ok())
+            .find_map(|message| {
+                message
+                    .get("result")
+                    .and_then(protocol_version)
+                    .map(str::to_string)
+            });
+        if version.is_some() {
+            self.set_protocol_version(version.as_deref());
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::Config;
+use crate::oauth::OAuthClient;
+use crate::retry::RetryPolicy;
+use crate::streamer::McpStreamClient;
+use crate::streamer_new::build_headers;
+use reqwest::header::HeaderMap;
+use std::sync::Arc;
+
+/// reloadable settings, built completely before any of them is applied
+struct Reloaded {
+    headers: HeaderMap,
+    retry: RetryPolicy,
+    oauth: Option<OAuthClient>,
+}
+
+impl Reloaded {
+    fn build(config: &Config) -> Result<Self, String> {
+        Ok(Self {
+            headers: build_headers(config).map_err(|e| e.to_string())?,
+            retry: RetryPolicy::from_config(config),
+            oauth: OAuthClient::from_config(config),
+        })
+    }
+}
+
+impl McpStreamClient {
+    /// applies reloadable settings from `config`: auth, headers and retries;
+    /// the session and open connections are kept
+    /// # Errors
+    /// * invalid auth or custom header, nothing is changed then
+    pub fn reload(&self, config: &Config) -> Result<(), String> {
+        let own = Reloaded::build(config)?;
+        let upstreams = self
+            .upstreams
+            .iter()
+            .map(|upstream| Ok((&upstream.client, Reloaded::build(config)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        for (client, reloaded) in upstreams {
+            client.apply(reloaded);
+        }
+        self.apply(own);
+        Ok(())
+    }
+
+    fn apply(&self, reloaded: Reloaded) {
+        self.static_headers.store(Arc::new(reloaded.headers));
+        self.retry.store(Arc::new(reloaded.retry));
+        self.oauth.store(Arc::new(reloaded.oauth));
+    }
+}
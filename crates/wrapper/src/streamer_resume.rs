@@ -0,0 +1,137 @@
+use crate::json_rpc_id_fast::parse_field_fast;
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::protocol::protocol_version;
+use crate::session_store::{SessionState, save_session};
+use crate::streamer::McpStreamClient;
+use crate::streamer_post::{read_body, read_error_body};
+use bytes::Bytes;
+use jsonrpc_core::Id;
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const INITIALIZE: &str = "initialize";
+const INITIALIZED: &[u8] = br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+
+fn is_method(payload: &[u8], method: &str) -> bool {
+    matches!(parse_field_fast(payload, "method"), Id::Str(m) if m == method)
+}
+
+impl McpStreamClient {
+    fn saved_state(&self) -> Option<SessionState> {
+        self.saved_session
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())
+    }
+
+    /// answers initialize from the saved session instead of starting a new one
+    pub(crate) fn resumed_initialize(&self, payload: &[u8]) -> Option<PostResult> {
+        let state = self.saved_state()?;
+        if !self.is_ready() || !is_method(payload, INITIALIZE) {
+            return None;
+        }
+        let request: Value = serde_json::from_slice(payload).ok()?;
+        // a client with different capabilities needs a fresh session, the
+        // old one must neither be sent along nor replayed when rejected
+        if request.get("params") != state.initialize_request.get("params") {
+            info!("Initialize differs from the saved session, starting a new one");
+            self.session_id.store(Arc::new(None));
+            if let Ok(mut guard) = self.saved_session.lock() {
+                *guard = None;
+            }
+            return None;
+        }
+        info!("Resuming saved MCP session");
+        self.set_protocol_version(protocol_version(&state.initialize_result));
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request.get("id"),
+            "result": state.initialize_result,
+        });
+        Some(PostResult {
+            out: vec![Bytes::from(response.to_string())],
+            sse: false,
+        })
+    }
+
+    /// persists a successful initialize when `--session-file` is set
+    pub(crate) fn remember_initialize(&self, payload: &[u8], res: &PostResult) {
+        let Some(path) = &self.config.session_file else {
+            return;
+        };
+        if !is_method(payload, INITIALIZE) {
+            return;
+        }
+        let (Some(session_id), Ok(initialize_request)) =
+            (self.get_session_id(), serde_json::from_slice(payload))
+        else {
+            return;
+        };
+        let Some(initialize_result) = res
+            .messages()
+            .iter()
+            .filter_map(|msg| serde_json::from_slice::<Value>(msg).ok())
+            .find_map(|mut msg| msg.get_mut("result").map(Value::take))
+        else {
+            return;
+        };
+
+        let state = SessionState {
+            url: self.url.clone(),
+            session_id,
+            initialize_request,
+            initialize_result,
+        };
+        if let Err(e) = save_session(path, &state) {
+            warn!("Failed to save session to {}: {e}", path.display());
+        }
+        if let Ok(mut guard) = self.saved_session.lock() {
+            *guard = Some(state);
+        }
+    }
+
+    /// Returns `true` if the upstream rejected a saved session
+    pub(crate) fn session_rejected(&self, status: StatusCode) -> bool {
+        matches!(status, StatusCode::NOT_FOUND | StatusCode::BAD_REQUEST)
+            && self.is_ready()
+            && self.saved_state().is_some()
+    }
+
+    /// replays the saved initialize to get a new session
+    /// # Errors
+    /// * upstream rejects the initialize request
+    pub(crate) async fn reinitialize(
+        &self,
+        client: &Client,
+        payload: &[u8],
+    ) -> Result<(), PostError> {
+        let state = self
+            .saved_state()
+            .ok_or("No saved session to re-initialize")?;
+        info!("Saved session rejected by upstream, re-initializing");
+        self.session_id.store(Arc::new(None));
+
+        let request = Bytes::from(state.initialize_request.to_string());
+        let response = self
+            .prepare_and_send_request(client, request.clone())
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = read_error_body(response, self.config.max_response_bytes).await?;
+            return Err(PostError::Status { status, body });
+        }
+        self.process_session_id(&response);
+        let res = read_body(response, self.config.max_response_bytes).await?;
+        self.observe_initialize(&request, &res);
+        self.remember_initialize(&request, &res);
+
+        // the client already sent it once for the old session
+        if !is_method(payload, "notifications/initialized") {
+            self.prepare_and_send_request(client, INITIALIZED).await?;
+        }
+        Ok(())
+    }
+}
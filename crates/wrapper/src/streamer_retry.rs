@@ -0,0 +1,44 @@
+use crate::json_rpc_id_fast::parse_field_fast;
+use crate::metrics::record_retry;
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::streamer::McpStreamClient;
+use bytes::Bytes;
+use jsonrpc_core::Id;
+use reqwest::Client;
+use tracing::warn;
+
+impl McpStreamClient {
+    /// [`Self::stream_post_timeout`] with the configured retry policy applied
+    /// # Errors
+    /// * last error once retries are exhausted or not allowed
+    pub async fn post_with_retry(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let policy = self.retry.load_full();
+        let method = match parse_field_fast(&payload, "method") {
+            Id::Str(method) => Some(method),
+            _ => None,
+        };
+
+        let mut attempt = 0;
+        loop {
+            match self.stream_post_timeout(client, payload.clone()).await {
+                Ok(res) => return Ok(res),
+                Err(e) if policy.should_retry(attempt, &e, method.as_deref()) => {
+                    let delay = policy.delay(attempt);
+                    attempt += 1;
+                    record_retry();
+                    warn!(
+                        "Upstream request failed, retry {attempt}/{} in {delay:?}: {e}",
+                        policy.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
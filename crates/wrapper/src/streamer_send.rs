@@ -1,5 +1,7 @@
+use crate::post_error::PostError;
+use crate::protocol::{PROTOCOL_VERSION_HEADER, sends_version_header};
 use crate::streamer::{McpStreamClient, SID};
-use reqwest::{Client, Response};
+use reqwest::{Client, RequestBuilder, Response};
 
 impl McpStreamClient {
     /// prepare and send request
@@ -7,23 +9,57 @@ impl McpStreamClient {
         &self, //
         client: &Client,
         payload: impl Into<reqwest::Body>,
-    ) -> Result<Response, String> {
-        let url = &self.config.mcp_server_url;
-        let mut request = client.post(url).body(payload);
+    ) -> Result<Response, PostError> {
+        self.send_to(client, &self.url, payload).await
+    }
+
+    /// posts payload to the given url with all client headers
+    pub(crate) async fn send_to(
+        &self,
+        client: &Client,
+        url: &str,
+        payload: impl Into<reqwest::Body>,
+    ) -> Result<Response, PostError> {
+        let request = self
+            .with_headers(client, client.post(url).body(payload))
+            .await?;
+
+        self.touch();
+        let response = request
+            .send()
+            .await
+            .map_err(|e| PostError::Transport(format!("Request failed: {e}")))?;
+        Ok(response)
+    }
 
-        for (key, value) in &self.static_headers {
+    /// adds static, OAuth and session headers to a request
+    pub(crate) async fn with_headers(
+        &self,
+        client: &Client,
+        mut request: RequestBuilder,
+    ) -> Result<RequestBuilder, String> {
+        let static_headers = self.static_headers.load_full();
+        for (key, value) in static_headers.iter() {
             request = request.header(key, value);
         }
 
+        // OAuth tokens are fetched lazily and refreshed before they expire
+        let oauth = self.oauth.load_full();
+        if let Some(oauth) = oauth.as_ref() {
+            request = request.bearer_auth(oauth.access_token(client).await?);
+        }
+
         // Add dynamic session_id header if available
         if let Some(sid) = self.get_session_id() {
             request = request.header(SID, sid);
         }
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {e}"))?;
-        Ok(response)
+        // older servers predate the header, only newer versions send it
+        if let Some(version) = self.protocol_version()
+            && sends_version_header(&version)
+        {
+            request = request.header(PROTOCOL_VERSION_HEADER, version);
+        }
+        Ok(request)
     }
 }
@@ -0,0 +1,268 @@
+use crate::config::sanitize_url_for_debug;
+use crate::json_rpc_id_fast::{parse_field_fast, parse_id_fast};
+use crate::post_error::PostError;
+use crate::post_result::PostResult;
+use crate::sse_events::EventReader;
+use crate::streamer::McpStreamClient;
+use crate::streamer_mux::ServerRequests;
+use crate::streamer_post::read_error_body;
+use bytes::Bytes;
+use flume::Sender;
+use jsonrpc_core::Id;
+use reqwest::{Client, Url};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+/// long-lived event streams must outlive the per-request client timeout
+pub(crate) const EVENT_STREAM_TIMEOUT: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+const ENDPOINT_EVENT: &str = "endpoint";
+const MESSAGE_EVENT: &str = "message";
+
+type Pending = Arc<Mutex<HashMap<Id, oneshot::Sender<Bytes>>>>;
+
+/// legacy HTTP+SSE session: one GET event stream plus per-message POSTs
+pub(crate) struct SseSession {
+    endpoint: String,
+    pending: Pending,
+    closed: Arc<AtomicBool>,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for SseSession {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl SseSession {
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    fn register(&self, id: Id) -> oneshot::Receiver<Bytes> {
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, tx);
+        }
+        rx
+    }
+
+    fn forget(&self, id: &Id) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(id);
+        }
+    }
+}
+
+/// derives the legacy `/sse` endpoint from a streamable HTTP `/mcp` url
+#[must_use]
+pub fn legacy_sse_url(mcp_url: &str) -> String {
+    let Ok(mut url) = Url::parse(mcp_url) else {
+        return format!("{}/sse", mcp_url.trim_end_matches('/'));
+    };
+    let path = url.path().trim_end_matches('/');
+    let path = format!("{}/sse", path.strip_suffix("/mcp").unwrap_or(path));
+    url.set_path(&path);
+    url.to_string()
+}
+
+impl McpStreamClient {
+    /// Returns `true` once the server was detected as legacy SSE only
+    pub fn is_sse(&self) -> bool {
+        self.sse_active.load(Ordering::Acquire)
+    }
+
+    /// sends a message over the legacy SSE transport and waits for its response
+    pub(crate) async fn sse_post(
+        &self,
+        client: &Client,
+        payload: Bytes,
+    ) -> Result<PostResult, PostError> {
+        let session = self.sse_session(client).await?;
+        self.sse_active.store(true, Ordering::Release);
+
+        let id = parse_id_fast(&payload);
+        let waiter = (id != Id::Null).then(|| session.register(id.clone()));
+
+        let response = match self.send_to(client, &session.endpoint, payload).await {
+            Ok(response) => response,
+            Err(e) => {
+                session.forget(&id);
+                return Err(e);
+            }
+        };
+        let status = response.status();
+        if !status.is_success() {
+            session.forget(&id);
+            let err_text = read_error_body(response, self.config.max_response_bytes).await?;
+            return Err(PostError::Status {
+                status,
+                body: err_text,
+            });
+        }
+
+        let Some(waiter) = waiter else {
+            return Ok(PostResult {
+                out: Vec::new(),
+                sse: false,
+            });
+        };
+        // the POST was accepted, the response arrives later on the event stream
+        let limit = self.config.response_timeout();
+        let Ok(message) = tokio::time::timeout(limit, waiter).await else {
+            session.forget(&id);
+            return Err(PostError::Timeout(limit));
+        };
+        let message = message
+            .map_err(|_| PostError::Transport("SSE stream closed before response".to_string()))?;
+        Ok(PostResult {
+            out: vec![message],
+            sse: false,
+        })
+    }
+
+    async fn sse_session(&self, client: &Client) -> Result<Arc<SseSession>, PostError> {
+        let mut guard = self.sse.lock().await;
+        if let Some(session) = guard.as_ref()
+            && !session.is_closed()
+        {
+            return Ok(Arc::clone(session));
+        }
+        let session = Arc::new(self.sse_connect(client).await?);
+        *guard = Some(Arc::clone(&session));
+        Ok(session)
+    }
+
+    async fn sse_connect(&self, client: &Client) -> Result<SseSession, PostError> {
+        let url = self
+            .config
+            .sse_url
+            .clone()
+            .unwrap_or_else(|| legacy_sse_url(&self.url));
+        debug!("Opening legacy SSE stream {url}");
+
+        let request = self
+            .with_headers(client, client.get(&url).timeout(EVENT_STREAM_TIMEOUT))
+            .await?;
+        let response = request
+            .send()
+            .await
+            .map_err(|e| PostError::Transport(format!("SSE connect failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PostError::Status {
+                status,
+                body: "SSE connect rejected".to_string(),
+            });
+        }
+
+        let mut events = EventReader::new(response).with_limit(self.config.max_response_bytes);
+        let endpoint = loop {
+            match events.next_event().await? {
+                Some(event) if event.event == ENDPOINT_EVENT => break event.data,
+                Some(_) => {}
+                None => {
+                    return Err(PostError::Transport(
+                        "SSE stream closed before endpoint event".to_string(),
+                    ));
+                }
+            }
+        };
+        let endpoint = sse_endpoint(&url, &String::from_utf8_lossy(&endpoint))?;
+        debug!("Legacy SSE message endpoint {endpoint}");
+
+        let pending = Pending::default();
+        let closed = Arc::new(AtomicBool::new(false));
+        let reader = tokio::spawn(read_messages(
+            events,
+            Arc::clone(&pending),
+            Arc::clone(&closed),
+            self.push_tx.clone(),
+            self.server_requests.clone(),
+        ));
+
+        Ok(SseSession {
+            endpoint,
+            pending,
+            closed,
+            reader,
+        })
+    }
+}
+
+/// resolves the `endpoint` event against the SSE url; messages, and the
+/// credentials sent with them, never go to another scheme, host or port
+/// # Errors
+/// * endpoint is not a valid url or has a different origin
+pub fn sse_endpoint(sse_url: &str, endpoint: &str) -> Result<String, PostError> {
+    let base =
+        Url::parse(sse_url).map_err(|e| PostError::Other(format!("Invalid SSE url: {e}")))?;
+    let resolved = base
+        .join(endpoint)
+        .map_err(|e| PostError::Other(format!("Invalid SSE endpoint {endpoint}: {e}")))?;
+    if resolved.origin() != base.origin() {
+        return Err(PostError::Other(format!(
+            "SSE endpoint {} is not on the origin of the SSE stream",
+            sanitize_url_for_debug(resolved.as_str())
+        )));
+    }
+    Ok(resolved.to_string())
+}
+
+/// routes responses to waiting requests, everything else to the push channel
+async fn read_messages(
+    mut events: EventReader,
+    pending: Pending,
+    closed: Arc<AtomicBool>,
+    push: Sender<Bytes>,
+    requests: Option<ServerRequests>,
+) {
+    loop {
+        match events.next_event().await {
+            Ok(Some(event)) if event.event == MESSAGE_EVENT => {
+                let Some(unmatched) = complete_pending(&pending, event.data) else {
+                    continue;
+                };
+                if let Some(requests) = &requests {
+                    requests.note(&unmatched);
+                }
+                if push.send_async(unmatched).await.is_err() {
+                    break;
+                }
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => break,
+            Err(e) => {
+                error!("SSE {e}");
+                break;
+            }
+        }
+    }
+    debug!("SSE stream closed");
+    closed.store(true, Ordering::Release);
+    if let Ok(mut pending) = pending.lock() {
+        pending.clear();
+    }
+}
+
+fn complete_pending(pending: &Pending, message: Bytes) -> Option<Bytes> {
+    // server-initiated requests carry a method and may reuse client ids
+    if parse_field_fast(&message, "method") != Id::Null {
+        return Some(message);
+    }
+    let id = parse_id_fast(&message);
+    let waiter = pending.lock().ok().and_then(|mut p| p.remove(&id));
+    match waiter {
+        Some(tx) => {
+            let _ = tx.send(message);
+            None
+        }
+        None => Some(message),
+    }
+}
@@ -0,0 +1,78 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::affinity::SessionAffinity;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::mcp_workers::spawn_workers;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tests that HTTP/1.1 requests of a session take turns on the one connection.
+/// # Errors
+/// * client construction fails
+#[tokio::test]
+pub async fn test_affinity_lane() -> Result<(), String> {
+    let config = Config::from_cli(["test", "--url", "http://localhost/mcp"]);
+    let affinity = SessionAffinity::new(&config).await?;
+    let first = affinity.acquire().await;
+    assert!(first.is_some());
+    let second = tokio::time::timeout(Duration::from_millis(50), affinity.acquire()).await;
+    assert!(second.is_err(), "lane must be exclusive");
+    drop(first);
+    assert!(affinity.acquire().await.is_some());
+
+    let config = Config::from_cli(["test", "--url", "http://localhost/mcp", "--http2"]);
+    let affinity = SessionAffinity::new(&config).await?;
+    assert!(affinity.acquire().await.is_none());
+    Ok(())
+}
+
+/// Tests that session traffic still reaches the upstream with `--session-affinity`.
+/// # Errors
+/// * channel operations fail
+/// # Panics
+/// * a response is missing
+#[tokio::test]
+pub async fn test_affinity_workers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let init = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("initialize".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .create_async()
+        .await;
+    let pings = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("ping".into()))
+        .match_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{}}"#)
+        .expect(4)
+        .create_async()
+        .await;
+
+    let url = format!("{}/mcp", server.url());
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--session-affinity"]);
+    let client = Arc::new(McpStreamClient::try_new(config)?);
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    let _ = spawn_workers(4, &client, &rx_in, tx_out).await;
+
+    tx_in
+        .send_async(Bytes::from(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#,
+        ))
+        .await?;
+    rx_out.recv_async().await?;
+    for _ in 0..4 {
+        tx_in
+            .send_async(Bytes::from(r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#))
+            .await?;
+    }
+    for _ in 0..4 {
+        rx_out.recv_async().await?;
+    }
+    init.assert_async().await;
+    pings.assert_async().await;
+    Ok(())
+}
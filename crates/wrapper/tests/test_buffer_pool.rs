@@ -0,0 +1,60 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::buffer_pool::{BufferPool, MAX_RETAINED_CAPACITY};
+
+/// Tests that handed back buffers are reused and counted.
+/// # Panics
+/// Panics if a buffer is not reused or a counter is wrong.
+#[test]
+fn test_pool_reuses_buffers() {
+    let pool = BufferPool::new(2, 64);
+    let mut buffer = pool.get();
+    assert!(buffer.capacity() >= 64);
+    buffer.extend_from_slice(b"{\"jsonrpc\":\"2.0\",\"id\":1}");
+    let ptr = buffer.as_ptr();
+
+    // a frozen line comes back once its last reference is handed in
+    pool.recycle(buffer.freeze());
+    assert_eq!(pool.idle(), 1);
+
+    let reused = pool.get();
+    assert!(reused.is_empty());
+    assert_eq!(reused.as_ptr(), ptr);
+
+    let snapshot = pool.snapshot();
+    assert_eq!(
+        (snapshot.hits, snapshot.misses, snapshot.recycled),
+        (1, 1, 1)
+    );
+    assert_eq!(snapshot.hit_rate_pct, 50);
+}
+
+/// Tests that shared, oversized and surplus buffers are not kept.
+/// # Panics
+/// Panics if such a buffer ends up in the pool.
+#[test]
+fn test_pool_drops_unusable_buffers() {
+    let pool = BufferPool::new(1, 64);
+
+    // still referenced elsewhere
+    let line = pool.get().freeze();
+    let held = line.clone();
+    pool.recycle(line);
+    assert_eq!(pool.idle(), 0);
+    drop(held);
+
+    // static bytes and buffers that grew too large
+    pool.recycle(Bytes::from_static(b"ping"));
+    let mut big = pool.get();
+    big.reserve(MAX_RETAINED_CAPACITY + 1);
+    pool.put(big);
+    assert_eq!(pool.idle(), 0);
+
+    // beyond the buffer limit
+    pool.put(pool.get());
+    pool.put(pool.get());
+    let (a, b) = (pool.get(), pool.get());
+    pool.put(a);
+    pool.put(b);
+    assert_eq!(pool.idle(), 1);
+    assert_eq!(pool.snapshot().recycled, 3);
+}
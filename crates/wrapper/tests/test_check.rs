@@ -0,0 +1,84 @@
+use mcp_stdio_wrapper::check::run_check;
+use mcp_stdio_wrapper::config::Config;
+use mockito::{Matcher, Server};
+
+/// Tests the `check` subcommand against a healthy and a failing server.
+/// # Panics
+/// Panics if the report does not match the server behaviour.
+#[tokio::test]
+pub async fn test_check() {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let _init = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("\"initialize\"".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","serverInfo":{"name":"gw","version":"1"}}}"#)
+        .create_async()
+        .await;
+    let _initialized = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("notifications/initialized".into()))
+        .with_status(202)
+        .create_async()
+        .await;
+    let list = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("tools/list".into()))
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"a"},{"name":"b"}]}}"#)
+        .create_async()
+        .await;
+
+    let report = run_check(Config::from_cli(["test", "--url", url.as_str()])).await;
+    assert!(report.ok, "{}", report.summary());
+    assert_eq!(report.exit_code(), 0);
+    assert_eq!(report.tools, Some(2));
+    assert_eq!(report.protocol_version.as_deref(), Some("2025-06-18"));
+    assert_eq!(report.steps.len(), 2);
+    list.remove_async().await;
+
+    let _broken = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("tools/list".into()))
+        .with_status(500)
+        .with_body("boom")
+        .create_async()
+        .await;
+    let report = run_check(Config::from_cli(["test", "--url", url.as_str()])).await;
+    assert!(!report.ok);
+    assert_eq!(report.exit_code(), 1);
+    assert!(report.summary().contains("FAIL"));
+    let json = serde_json::to_value(&report).unwrap_or_default();
+    assert_eq!(json["steps"][1]["ok"], false);
+}
+
+/// Tests that `check` gives up on a hanging server after `--request-timeout`.
+/// # Panics
+/// Panics if the check does not fail in time.
+#[tokio::test]
+pub async fn test_check_request_timeout() {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let _hang = server
+        .mock("POST", "/mcp")
+        .with_chunked_body(|_| {
+            std::thread::sleep(std::time::Duration::from_secs(3));
+            Ok(())
+        })
+        .create_async()
+        .await;
+
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        url.as_str(),
+        "--request-timeout",
+        "1",
+        "--max-retries",
+        "0",
+    ]);
+    let started = std::time::Instant::now();
+    let report = run_check(config).await;
+    assert_eq!(report.exit_code(), 1);
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+}
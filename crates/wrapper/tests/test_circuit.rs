@@ -0,0 +1,132 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::circuit::{CircuitBreaker, is_outage};
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::post_error::PostError;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+use reqwest::StatusCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tests opening and closing the breaker.
+/// # Panics
+/// Panics if the breaker state is wrong.
+#[test]
+pub fn test_circuit_breaker() {
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(1));
+    assert!(!breaker.record_failure());
+    assert!(!breaker.is_open());
+    assert!(breaker.record_failure());
+    assert!(breaker.is_open());
+    assert!(!breaker.record_failure());
+    breaker.record_success();
+    assert!(!breaker.is_open());
+    assert_eq!(breaker.failures(), 0);
+
+    let disabled = CircuitBreaker::new(0, Duration::from_secs(1));
+    for _ in 0..10 {
+        assert!(!disabled.record_failure());
+    }
+    assert!(!disabled.is_open());
+
+    assert!(is_outage(&PostError::Transport("reset".into())));
+    assert!(is_outage(&PostError::Status {
+        status: StatusCode::BAD_GATEWAY,
+        body: String::new()
+    }));
+    assert!(!is_outage(&PostError::Status {
+        status: StatusCode::NOT_FOUND,
+        body: String::new()
+    }));
+    // a failing tool call is not an outage
+    assert!(!is_outage(&PostError::Status {
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+        body: String::new()
+    }));
+}
+
+/// Tests that requests fail fast once the circuit is open.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if a request reaches the server while the circuit is open.
+#[tokio::test]
+pub async fn test_post_guarded() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let mock = server
+        .mock("POST", "/mcp")
+        .with_status(502)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        url.as_str(),
+        "--max-retries",
+        "0",
+        "--circuit-threshold",
+        "2",
+        "--circuit-probe-ms",
+        "60000",
+    ]);
+    let http_client = get_http_client(&config).await?;
+    let cli = Arc::new(McpStreamClient::try_new(config)?);
+
+    for _ in 0..2 {
+        let out = cli.post_guarded(&http_client, Bytes::from("{}")).await;
+        assert!(matches!(out, Err(PostError::Status { .. })));
+    }
+    let out = cli.post_guarded(&http_client, Bytes::from("{}")).await;
+    assert!(matches!(out, Err(PostError::Other(msg)) if msg.starts_with("Upstream unavailable")));
+    mock.assert_async().await;
+    Ok(())
+}
+
+/// Tests that the circuit probe stops once the client is dropped.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the probe keeps pinging a dropped client's upstream.
+#[tokio::test]
+pub async fn test_probe_stops_with_client() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let failing = server
+        .mock("POST", "/mcp")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        url.as_str(),
+        "--max-retries",
+        "0",
+        "--circuit-threshold",
+        "1",
+        "--circuit-probe-ms",
+        "50",
+    ]);
+    let http_client = get_http_client(&config).await?;
+    let cli = Arc::new(McpStreamClient::try_new(config)?);
+    let _ = cli.post_guarded(&http_client, Bytes::from("{}")).await;
+    failing.assert_async().await;
+    failing.remove_async().await;
+    drop(cli);
+
+    let probe = server
+        .mock("POST", "/mcp")
+        .with_status(503)
+        .expect(0)
+        .create_async()
+        .await;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    probe.assert_async().await;
+    Ok(())
+}
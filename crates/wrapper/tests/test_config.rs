@@ -27,11 +27,29 @@ pub fn test_config_debug_redacts_auth() {
 pub fn test_config_debug_redacts_url_credentials() {
     let mut url = Url::parse("https://example.com/mcp").expect("valid URL");
     url.set_username("user").expect("set username");
-    url.set_password(Some("secret")).expect("set password");
+    url.set_password(Some("hunter2")).expect("set password");
     let config = Config::from_cli(["wrapper", "--url", url.as_str()]);
 
     let rendered = format!("{config:?}");
-    assert!(!rendered.contains("secret"));
+    assert!(!rendered.contains("hunter2"));
     assert!(!rendered.contains("user:"));
     assert!(rendered.contains("redacted@example.com"));
 }
+
+#[test]
+pub fn test_config_url_keeps_commas() {
+    let config = Config::from_cli(["wrapper", "--url", "http://localhost/mcp?fields=a,b"]);
+    assert_eq!(
+        config.mcp_server_url,
+        vec!["http://localhost/mcp?fields=a,b"]
+    );
+
+    let config = Config::from_cli([
+        "wrapper",
+        "--url",
+        "a=http://localhost/a",
+        "--url",
+        "b=http://localhost/b",
+    ]);
+    assert_eq!(config.mcp_server_url.len(), 2);
+}
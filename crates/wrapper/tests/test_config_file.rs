@@ -0,0 +1,125 @@
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::config_env::expand_env;
+use std::io::Write;
+
+const FILE: &str = r#"
+url = "http://localhost:4444/servers/abc/mcp"
+auth-token = "${WRAPPER_TEST_TOKEN}"
+concurrency = 4
+header = ["X-Tenant: acme", "X-Region: ${WRAPPER_TEST_REGION:-eu}"]
+
+[logging]
+log_level = "info"
+
+[http]
+http2 = true
+"#;
+
+fn write_config(contents: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(contents.as_bytes()).unwrap();
+    file
+}
+
+#[test]
+pub fn test_expand_env() {
+    // SAFETY: variable name is unique to this test.
+    unsafe {
+        std::env::set_var("WRAPPER_EXPAND_TEST", "value");
+    }
+    assert_eq!(
+        expand_env("a ${WRAPPER_EXPAND_TEST} b").unwrap(),
+        "a value b"
+    );
+    assert_eq!(expand_env("$$5 and $HOME").unwrap(), "$5 and $HOME");
+    assert_eq!(
+        expand_env("${WRAPPER_EXPAND_MISSING:-fallback}").unwrap(),
+        "fallback"
+    );
+    assert!(expand_env("${WRAPPER_EXPAND_MISSING}").is_err());
+    assert!(expand_env("${WRAPPER_EXPAND_TEST").is_err());
+}
+
+#[test]
+pub fn test_config_file() {
+    // SAFETY: variable name is unique to this test.
+    unsafe {
+        std::env::set_var("WRAPPER_TEST_TOKEN", "file-token");
+    }
+    let file = write_config(FILE);
+    let path = file.path().to_str().unwrap();
+
+    let config = Config::from_cli(["wrapper", "--config", path]);
+    assert_eq!(
+        config.mcp_server_url,
+        vec!["http://localhost:4444/servers/abc/mcp"]
+    );
+    assert_eq!(config.auth_token.as_deref(), Some("file-token"));
+    assert_eq!(config.concurrency, 4);
+    assert_eq!(config.headers, vec!["X-Tenant: acme", "X-Region: eu"]);
+    assert_eq!(config.mcp_wrapper_log_level, "info");
+    assert!(config.http2);
+}
+
+#[test]
+pub fn test_config_file_cli_overrides() {
+    let file = write_config("url = \"http://file/mcp\"\nconcurrency = 4\n");
+    let path = file.path().to_str().unwrap();
+
+    let config = Config::from_cli([
+        "wrapper",
+        "--url",
+        "http://cli/mcp",
+        "--config",
+        path,
+        "--concurrency=2",
+    ]);
+    assert_eq!(config.mcp_server_url, vec!["http://cli/mcp"]);
+    assert_eq!(config.concurrency, 2);
+}
+
+#[test]
+pub fn test_config_file_cli_aliases_override() {
+    let file = write_config(
+        "url = \"http://file/mcp\"\ntls_cert = \"/file/ca.pem\"\nmcp_content_type = \"text/file\"\n",
+    );
+    let path = file.path().to_str().unwrap();
+
+    let config = Config::try_from_cli([
+        "wrapper",
+        "--ca-cert",
+        "/cli/ca.pem",
+        "-capplication/cli",
+        "--config",
+        path,
+    ])
+    .expect("command line wins over the file");
+    assert_eq!(
+        config.tls_cert.as_deref(),
+        Some(std::path::Path::new("/cli/ca.pem"))
+    );
+    assert_eq!(config.mcp_content_type, "application/cli");
+}
+
+#[test]
+pub fn test_cli_values_expand_env() {
+    // SAFETY: variable names are unique to this test.
+    unsafe {
+        std::env::set_var("WRAPPER_CLI_HOST", "gw.example");
+        std::env::set_var("WRAPPER_CLI_KEY", "k-123");
+    }
+    let config = Config::from_cli([
+        "wrapper",
+        "--url",
+        "https://${WRAPPER_CLI_HOST}/mcp",
+        "--header",
+        "X-Api-Key: ${WRAPPER_CLI_KEY}",
+    ]);
+    assert_eq!(config.mcp_server_url, vec!["https://gw.example/mcp"]);
+    assert_eq!(config.headers, vec!["X-Api-Key: k-123"]);
+
+    let mut missing = Config::from_cli(["wrapper", "--url", "http://localhost/mcp"]);
+    missing.mcp_server_url = vec!["http://${WRAPPER_CLI_MISSING}/mcp".to_string()];
+    let err = missing.resolve_env().unwrap_err();
+    assert!(err.contains("WRAPPER_CLI_MISSING"), "{err}");
+}
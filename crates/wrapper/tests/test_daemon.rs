@@ -0,0 +1,127 @@
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::daemon::{
+    Role, current_role, pid_path, read_pid, stop_pid_path, write_pid_file,
+};
+use std::ffi::OsString;
+use std::path::Path;
+
+#[test]
+fn daemon_requires_listen() {
+    assert!(Config::try_from_cli(["test", "--url", "http://localhost/mcp", "--daemon"]).is_err());
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        "http://localhost/mcp",
+        "--listen",
+        "unix:/tmp/wrapper.sock",
+        "--daemon",
+        "--pid-file",
+        "/tmp/wrapper.pid",
+    ]);
+    assert!(config.daemon);
+    assert_eq!(
+        pid_path(config.pid_file.as_deref()).ok().as_deref(),
+        Some(Path::new("/tmp/wrapper.pid"))
+    );
+    assert_eq!(current_role(), Role::Launcher);
+}
+
+#[test]
+fn stop_reads_pid_file_flag() {
+    let args: Vec<OsString> = vec!["--pid-file".into(), "/run/a.pid".into()];
+    assert_eq!(
+        stop_pid_path(&args).ok().as_deref(),
+        Some(Path::new("/run/a.pid"))
+    );
+    let args: Vec<OsString> = vec!["--pid-file=/run/b.pid".into()];
+    assert_eq!(
+        stop_pid_path(&args).ok().as_deref(),
+        Some(Path::new("/run/b.pid"))
+    );
+}
+
+/// # Errors
+/// * temp dir not writable
+#[test]
+fn pid_file_round_trip() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("wrapper.pid");
+    assert!(read_pid(&path).is_err());
+
+    write_pid_file(&path, 4_000_000)?;
+    assert_eq!(read_pid(&path)?, 4_000_000);
+
+    // a pid file of a running process is not replaced
+    #[cfg(unix)]
+    {
+        write_pid_file(&path, std::process::id())?;
+        assert!(write_pid_file(&path, 4_000_001).is_err());
+    }
+    Ok(())
+}
+
+/// # Errors
+/// * temp dir not writable
+#[cfg(unix)]
+#[test]
+fn pid_file_does_not_follow_symlink() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let target = dir.path().join("target");
+    std::fs::write(&target, "keep\n")?;
+    let path = dir.path().join("wrapper.pid");
+    std::os::unix::fs::symlink(&target, &path)?;
+
+    write_pid_file(&path, 4_000_000)?;
+    assert_eq!(std::fs::read_to_string(&target)?, "keep\n");
+    assert!(std::fs::symlink_metadata(&path)?.is_file());
+    assert_eq!(read_pid(&path)?, 4_000_000);
+    Ok(())
+}
+
+/// the launcher reports success only once the supervisor owns the pid file
+/// # Errors
+/// * temp dir not writable or the binary does not start
+#[cfg(unix)]
+#[test]
+fn launcher_waits_for_supervisor() -> std::io::Result<()> {
+    use std::process::Command;
+
+    let dir = tempfile::tempdir()?;
+    let pid_file = dir.path().join("wrapper.pid");
+    let listen = format!("unix:{}", dir.path().join("wrapper.sock").display());
+    let launch = || {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_mcp_stdio_wrapper"));
+        command
+            .args(["--url", "http://127.0.0.1:9/mcp", "--daemon", "--listen"])
+            .arg(&listen)
+            .arg("--pid-file")
+            .arg(&pid_file);
+        command.output()
+    };
+
+    // pid 1 always runs, so the supervisor must refuse the file
+    std::fs::write(&pid_file, "1\n")?;
+    let refused = launch()?;
+    assert!(!refused.status.success());
+    assert!(
+        String::from_utf8_lossy(&refused.stderr).contains("already running"),
+        "{refused:?}"
+    );
+
+    std::fs::remove_file(&pid_file)?;
+    let started = launch()?;
+    assert!(started.status.success(), "{started:?}");
+    let pid: u32 = String::from_utf8_lossy(&started.stdout)
+        .trim()
+        .parse()
+        .map_err(std::io::Error::other)?;
+    assert_eq!(read_pid(&pid_file)?, pid);
+
+    let stopped = Command::new(env!("CARGO_BIN_EXE_mcp_stdio_wrapper"))
+        .arg("stop")
+        .arg("--pid-file")
+        .arg(&pid_file)
+        .output()?;
+    assert!(stopped.status.success(), "{stopped:?}");
+    Ok(())
+}
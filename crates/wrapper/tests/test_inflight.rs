@@ -0,0 +1,28 @@
+use mcp_stdio_wrapper::inflight::{Admission, InFlight};
+
+const CALL: &[u8] = br#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"a"}}"#;
+
+/// Tests that identical in-flight requests are reported as duplicates.
+/// # Panics
+/// Panics if a duplicate is admitted or an id is not released.
+#[test]
+pub fn test_inflight_duplicates() {
+    let inflight = InFlight::default();
+
+    let first = inflight.begin(CALL);
+    assert!(matches!(first, Admission::Tracked(_)));
+    assert!(matches!(inflight.begin(CALL), Admission::Duplicate(_)));
+
+    // same id, different request: forwarded, upstream decides
+    let other = br#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"b"}}"#;
+    assert!(matches!(inflight.begin(other), Admission::Untracked));
+
+    // notifications carry no id
+    let notify = br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+    assert!(matches!(inflight.begin(notify), Admission::Untracked));
+    assert_eq!(inflight.len(), 1);
+
+    drop(first);
+    assert!(inflight.is_empty());
+    assert!(matches!(inflight.begin(CALL), Admission::Tracked(_)));
+}
@@ -0,0 +1,41 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::json_rpc_batch::{
+    expects_response, is_batch, is_response, join_batch, split_batch,
+};
+
+#[test]
+fn detects_batches() {
+    assert!(is_batch(b"  [{}]"));
+    assert!(!is_batch(br#"{"jsonrpc":"2.0"}"#));
+    assert!(!is_batch(b""));
+}
+
+#[test]
+fn splits_and_joins() {
+    let entries = split_batch(br#"[{"id":1}, {"method":"x"}]"#).unwrap();
+    assert_eq!(
+        entries,
+        [Bytes::from(r#"{"id":1}"#), Bytes::from(r#"{"method":"x"}"#)]
+    );
+    assert!(split_batch(b"[{").is_err());
+
+    assert_eq!(
+        join_batch(&entries).unwrap(),
+        Bytes::from(r#"[{"id":1},{"method":"x"}]"#)
+    );
+    assert!(join_batch(&[]).is_none());
+}
+
+#[test]
+fn classifies_messages() {
+    assert!(is_response(br#"{"jsonrpc":"2.0","id":1,"result":{}}"#));
+    assert!(!is_response(
+        br#"{"jsonrpc":"2.0","method":"notifications/progress"}"#
+    ));
+    assert!(expects_response(
+        br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#
+    ));
+    assert!(!expects_response(
+        br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#
+    ));
+}
@@ -0,0 +1,55 @@
+use jsonrpc_core::ErrorCode;
+use mcp_stdio_wrapper::json_rpc_validate::validate_message;
+
+/// Tests JSON-RPC 2.0 structure checks.
+/// # Panics
+/// Panics if a message is classified wrongly.
+#[test]
+pub fn test_validate_message() {
+    let valid = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":"a","method":"tools/call","params":{"name":"x"}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"result":{}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"error":{"code":-1,"message":"no"}}"#,
+        r#"[{"jsonrpc":"2.0","id":1,"method":"ping"}]"#,
+    ];
+    for line in valid {
+        assert_eq!(validate_message(line.as_bytes()), Ok(()), "{line}");
+    }
+
+    let invalid = [
+        ("{", ErrorCode::ParseError),
+        ("[]", ErrorCode::InvalidRequest),
+        ("42", ErrorCode::InvalidRequest),
+        (r#"{"id":1,"method":"ping"}"#, ErrorCode::InvalidRequest),
+        (
+            r#"{"jsonrpc":"2.0","id":{},"method":"ping"}"#,
+            ErrorCode::InvalidRequest,
+        ),
+        (
+            r#"{"jsonrpc":"2.0","id":1,"method":7}"#,
+            ErrorCode::InvalidRequest,
+        ),
+        (
+            r#"{"jsonrpc":"2.0","id":1,"method":"a","params":1}"#,
+            ErrorCode::InvalidRequest,
+        ),
+        (r#"{"jsonrpc":"2.0","id":1}"#, ErrorCode::InvalidRequest),
+        (
+            r#"{"jsonrpc":"2.0","result":{}}"#,
+            ErrorCode::InvalidRequest,
+        ),
+        (
+            r#"[{"jsonrpc":"2.0","id":1,"method":"ping"},1]"#,
+            ErrorCode::InvalidRequest,
+        ),
+    ];
+    for (line, code) in invalid {
+        assert_eq!(
+            validate_message(line.as_bytes()).map_err(|e| e.code),
+            Err(code),
+            "{line}"
+        );
+    }
+}
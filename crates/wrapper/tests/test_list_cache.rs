@@ -0,0 +1,76 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::list_cache::ListCache;
+use mcp_stdio_wrapper::post_result::PostResult;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+use std::time::Duration;
+
+const LIST: &[u8] = br#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#;
+const RESULT: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[]}}"#;
+
+/// Tests keys, id rewriting and invalidation.
+/// # Panics
+/// Panics if the cache answers when it should not.
+#[test]
+pub fn test_list_cache() {
+    assert_eq!(ListCache::new(Duration::ZERO).key(LIST), None);
+
+    let cache = ListCache::new(Duration::from_secs(60));
+    assert_eq!(cache.key(br#"{"id":1,"method":"tools/call"}"#), None);
+    let key = cache.key(LIST).unwrap_or_default();
+    assert_ne!(
+        Some(key.as_str()),
+        cache
+            .key(br#"{"id":1,"method":"tools/list","params":{"cursor":"2"}}"#)
+            .as_deref()
+    );
+
+    let res = PostResult {
+        out: vec![Bytes::from(RESULT)],
+        sse: false,
+    };
+    cache.put(key.clone(), &res);
+    let hit = cache.get(&key, br#"{"jsonrpc":"2.0","id":"x","method":"tools/list"}"#);
+    let out = hit.map(|r| r.messages()).unwrap_or_default();
+    assert_eq!(
+        out,
+        vec![Bytes::from(
+            r#"{"id":"x","jsonrpc":"2.0","result":{"tools":[]}}"#
+        )]
+    );
+
+    cache.observe(br#"{"jsonrpc":"2.0","method":"notifications/prompts/list_changed"}"#);
+    assert!(cache.get(&key, LIST).is_some());
+    cache.observe(br#"{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}"#);
+    assert!(cache.get(&key, LIST).is_none());
+}
+
+/// Tests that a repeated tools/list is not sent upstream.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the upstream sees more than one request.
+#[tokio::test]
+pub async fn test_list_cache_post() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let mock = server
+        .mock("POST", "/mcp")
+        .with_body(RESULT)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--list-cache-ttl", "60"]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    for _ in 0..3 {
+        let out = cli.stream_post(&http_client, Bytes::from(LIST)).await?;
+        assert_eq!(out.messages().len(), 1);
+    }
+    mock.assert_async().await;
+    Ok(())
+}
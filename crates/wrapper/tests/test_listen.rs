@@ -0,0 +1,128 @@
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::listen::{ListenAddr, parse_listen};
+use std::path::PathBuf;
+
+/// Tests `--listen` address parsing.
+/// # Panics
+/// Panics if an address is parsed wrongly.
+#[test]
+pub fn test_parse_listen() {
+    assert_eq!(
+        parse_listen("unix:/tmp/mcp.sock"),
+        Ok(ListenAddr::Unix(PathBuf::from("/tmp/mcp.sock")))
+    );
+    assert_eq!(
+        parse_listen(r"pipe:\\.\pipe\mcp"),
+        Ok(ListenAddr::Pipe(r"\\.\pipe\mcp".to_string()))
+    );
+    for bad in ["", "unix:", "tcp:127.0.0.1:1", "/tmp/mcp.sock"] {
+        assert!(parse_listen(bad).is_err(), "{bad}");
+    }
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        "http://localhost/mcp",
+        "--listen",
+        "unix:/x",
+    ]);
+    assert_eq!(config.listen.as_deref(), Some("unix:/x"));
+}
+
+/// Tests that two socket clients are served by one wrapper.
+/// # Errors
+/// Returns an error if the mock server or socket setup fails.
+/// # Panics
+/// Panics if a client does not get its response.
+#[cfg(unix)]
+#[tokio::test]
+pub async fn test_listen_unix() -> Result<(), Box<dyn std::error::Error>> {
+    use mcp_stdio_wrapper::listen::serve;
+    use mockito::Server;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let mock = server
+        .mock("POST", "/mcp")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("wrapper.sock");
+    let listen = format!("unix:{}", path.display());
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        url.as_str(),
+        "--listen",
+        listen.as_str(),
+        "--no-event-stream",
+    ]);
+    let listener = tokio::spawn(serve(config));
+    while !path.exists() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    for _ in 0..2 {
+        let mut stream = UnixStream::connect(&path).await?;
+        stream
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n")
+            .await?;
+        let mut line = String::new();
+        let mut reader = BufReader::new(stream);
+        tokio::time::timeout(Duration::from_secs(5), reader.read_line(&mut line)).await??;
+        assert!(line.contains("\"result\""), "{line}");
+    }
+    mock.assert_async().await;
+    listener.abort();
+    Ok(())
+}
+
+/// Tests that the socket is private and a live socket is not taken over.
+/// # Errors
+/// Returns an error if the socket setup fails.
+/// # Panics
+/// Panics if the mode is wrong or a second wrapper replaces the first.
+#[cfg(unix)]
+#[tokio::test]
+pub async fn test_listen_unix_owned() -> Result<(), Box<dyn std::error::Error>> {
+    use mcp_stdio_wrapper::listen::serve;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::Duration;
+    use tokio::net::UnixStream;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("wrapper.sock");
+    let listen = format!("unix:{}", path.display());
+    let args = [
+        "test",
+        "--url",
+        "http://127.0.0.1:9/mcp",
+        "--listen",
+        listen.as_str(),
+        "--no-event-stream",
+    ];
+    let listener = tokio::spawn(serve(Config::from_cli(args)));
+    while UnixStream::connect(&path).await.is_err() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while std::fs::metadata(&path).is_ok_and(|m| m.permissions().mode() & 0o777 != 0o600) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await?;
+
+    let second = serve(Config::from_cli(args)).await;
+    assert_eq!(
+        second.err().map(|e| e.kind()),
+        Some(std::io::ErrorKind::AddrInUse)
+    );
+    assert!(UnixStream::connect(&path).await.is_ok());
+    listener.abort();
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use mcp_stdio_wrapper::logger_json::{JsonFields, JsonFormat};
+use serde_json::Value;
+use std::io;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info_span};
+use tracing_subscriber::fmt::MakeWriter;
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| io::ErrorKind::Other)?
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Buffer {
+    type Writer = Self;
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// # Panics
+/// * a record is not one JSON object per line
+#[test]
+fn json_records_carry_span_and_event_fields() {
+    let buffer = Buffer::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(buffer.clone())
+        .event_format(JsonFormat)
+        .fmt_fields(JsonFields)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = info_span!("worker", worker = 3).entered();
+        debug!(
+            request_id = "7",
+            latency_ms = 12u64,
+            ok = true,
+            "Request finished"
+        );
+    });
+
+    let out = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let record: Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(record["level"], "DEBUG");
+    assert_eq!(record["worker"], 3);
+    assert_eq!(record["request_id"], "7");
+    assert_eq!(record["latency_ms"], 12);
+    assert_eq!(record["ok"], true);
+    assert_eq!(record["message"], "Request finished");
+    assert!(record["ts"].as_str().is_some_and(|ts| !ts.is_empty()));
+}
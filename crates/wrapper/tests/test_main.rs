@@ -5,5 +5,5 @@ fn test_init_main() {
     // Simulate command line arguments
     let fake_args = ["wrapper", "--url", "file:///tmp"];
     let config = init_main(fake_args.iter());
-    assert_eq!(config.mcp_server_url, "file:///tmp");
+    assert_eq!(config.mcp_server_url, vec!["file:///tmp"]);
 }
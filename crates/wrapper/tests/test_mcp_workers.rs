@@ -4,8 +4,9 @@ use mcp_stdio_wrapper::config::{Config, DEFAULT_CONCURRENCY};
 use mcp_stdio_wrapper::logger::init_logger;
 use mcp_stdio_wrapper::mcp_workers::*;
 use mcp_stdio_wrapper::streamer::McpStreamClient;
-use mockito::Server;
+use mockito::{Matcher, Server};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Tests that `spawn_workers` correctly processes a message by sending it to a mock server
 /// and forwarding the response.
@@ -43,7 +44,11 @@ pub async fn test_mcp_workers() -> Result<(), Box<dyn std::error::Error>> {
         let (tx_out, rx_out) = flume::unbounded();
 
         let _ = spawn_workers(DEFAULT_CONCURRENCY, &Arc::new(client), &rx_in, tx_out).await;
-        tx_in.send_async(Bytes::from("init")).await?;
+        tx_in
+            .send_async(Bytes::from(
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#,
+            ))
+            .await?;
 
         let out = rx_out.recv_async().await?;
 
@@ -52,3 +57,229 @@ pub async fn test_mcp_workers() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+/// Tests that malformed stdin lines are answered locally.
+/// # Errors
+/// Returns an error if channel operations fail.
+/// # Panics
+/// Panics if a line reaches the server or the error code is wrong.
+#[tokio::test]
+pub async fn test_mcp_workers_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let mock = server.mock("POST", "/mcp/").expect(0).create_async().await;
+
+    let url = format!("{}/mcp/", server.url());
+    let client = McpStreamClient::try_new(Config::from_cli(["test", "--url", url.as_str()]))?;
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    let _ = spawn_workers(1, &Arc::new(client), &rx_in, tx_out).await;
+
+    for (line, code) in [
+        ("not json", "-32700"),
+        (r#"{"jsonrpc":"1.0","id":5,"method":"ping"}"#, "-32600"),
+    ] {
+        tx_in.send_async(Bytes::from(line)).await?;
+        let out = String::from_utf8_lossy(&rx_out.recv_async().await?).to_string();
+        assert!(out.contains(code), "{out}");
+    }
+    mock.assert_async().await;
+    Ok(())
+}
+
+/// Tests that a batch is split into entries and answered with one array in request order.
+/// # Errors
+/// Returns an error if channel operations fail.
+/// # Panics
+/// Panics if the batch response is missing entries or out of order.
+#[tokio::test]
+pub async fn test_mcp_workers_batch() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let mut mocks = Vec::new();
+    for id in [1, 2] {
+        mocks.push(
+            server
+                .mock("POST", "/mcp/")
+                .match_body(Matcher::PartialJsonString(format!(r#"{{"id":{id}}}"#)))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(r#"{{"jsonrpc":"2.0","id":{id},"result":{{}}}}"#))
+                .create_async()
+                .await,
+        );
+    }
+    mocks.push(
+        server
+            .mock("POST", "/mcp/")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"method":"notifications/initialized"}"#.to_string(),
+            ))
+            .with_status(202)
+            .create_async()
+            .await,
+    );
+
+    let url = format!("{}/mcp/", server.url());
+    let client = McpStreamClient::try_new(Config::from_cli(["test", "--url", url.as_str()]))?;
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    let _ = spawn_workers(1, &Arc::new(client), &rx_in, tx_out).await;
+
+    tx_in
+        .send_async(Bytes::from(
+            r#"[{"jsonrpc":"2.0","id":1,"method":"ping"},
+                {"jsonrpc":"2.0","method":"notifications/initialized"},
+                {"jsonrpc":"1.0","id":3,"method":"ping"},
+                {"jsonrpc":"2.0","id":2,"method":"ping"}]"#
+                .replace('\n', ""),
+        ))
+        .await?;
+
+    let out: serde_json::Value = serde_json::from_slice(&rx_out.recv_async().await?)?;
+    let batch = out.as_array().ok_or("expected a batch response")?;
+    let ids: Vec<_> = batch.iter().map(|r| r["id"].clone()).collect();
+    assert_eq!(ids, [1, 3, 2]);
+    assert_eq!(batch[1]["error"]["code"], -32600);
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+    Ok(())
+}
+
+/// Tests that batch entries are posted no more than `--concurrency` at a time
+/// and still answered in request order.
+/// # Errors
+/// Returns an error if channel operations fail.
+/// # Panics
+/// Panics if entries overlap or come back out of order.
+#[tokio::test]
+pub async fn test_mcp_workers_batch_concurrency() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let active = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let mut mocks = Vec::new();
+    for id in 1..=4 {
+        let (active, peak) = (Arc::clone(&active), Arc::clone(&peak));
+        mocks.push(
+            server
+                .mock("POST", "/mcp/")
+                .match_body(Matcher::PartialJsonString(format!(r#"{{"id":{id}}}"#)))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_chunked_body(move |w| {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    w.write_all(
+                        format!(r#"{{"jsonrpc":"2.0","id":{id},"result":{{}}}}"#).as_bytes(),
+                    )
+                })
+                .create_async()
+                .await,
+        );
+    }
+
+    let url = format!("{}/mcp/", server.url());
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--concurrency", "1"]);
+    let client = McpStreamClient::try_new(config)?;
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    let _ = spawn_workers(1, &Arc::new(client), &rx_in, tx_out).await;
+
+    let batch: Vec<_> = (1..=4)
+        .map(|id| format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"ping"}}"#))
+        .collect();
+    tx_in
+        .send_async(Bytes::from(format!("[{}]", batch.join(","))))
+        .await?;
+
+    let out: serde_json::Value = serde_json::from_slice(&rx_out.recv_async().await?)?;
+    let ids: Vec<_> = out
+        .as_array()
+        .ok_or("expected a batch response")?
+        .iter()
+        .map(|r| r["id"].clone())
+        .collect();
+    assert_eq!(ids, [1, 2, 3, 4]);
+    assert_eq!(peak.load(Ordering::SeqCst), 1);
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+    Ok(())
+}
+
+/// Tests that a batch of notifications gets no answer.
+/// # Errors
+/// Returns an error if channel operations fail.
+/// # Panics
+/// Panics if anything is written for the batch.
+#[tokio::test]
+pub async fn test_mcp_workers_batch_notifications() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/mcp/")
+        .with_status(202)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let url = format!("{}/mcp/", server.url());
+    let client = McpStreamClient::try_new(Config::from_cli(["test", "--url", url.as_str()]))?;
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    let handles = spawn_workers(1, &Arc::new(client), &rx_in, tx_out).await;
+
+    tx_in
+        .send_async(Bytes::from(
+            r#"[{"jsonrpc":"2.0","method":"notifications/initialized"},{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":1}}]"#,
+        ))
+        .await?;
+    drop(tx_in);
+    for handle in handles {
+        handle.await?;
+    }
+    assert!(rx_out.try_recv().is_err());
+    mock.assert_async().await;
+    Ok(())
+}
+
+/// Tests that with `--ordered` a line rejected by the reader is answered
+/// after the slower request read before it.
+/// # Errors
+/// Returns an error if channel operations fail.
+/// # Panics
+/// Panics if the replies are out of order.
+#[tokio::test]
+pub async fn test_mcp_workers_ordered_rejection() -> Result<(), Box<dyn std::error::Error>> {
+    use mcp_stdio_wrapper::stdio_reader::spawn_limited_reader;
+    use std::time::Duration;
+
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/mcp/")
+        .with_status(200)
+        .with_chunked_body(|w| {
+            std::thread::sleep(Duration::from_millis(100));
+            w.write_all(br#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        })
+        .create_async()
+        .await;
+
+    let url = format!("{}/mcp/", server.url());
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--ordered"]);
+    let client = McpStreamClient::try_new(config)?;
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    let _ = spawn_workers(2, &Arc::new(client), &rx_in, tx_out).await;
+
+    let input: &[u8] = b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n\
+        {\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"ping\",\"params\":{\"pad\":\"xxxxxxxxxxxx\"}}\n";
+    let _ = spawn_limited_reader(tx_in, input, 48).await;
+
+    let first = rx_out.recv_async().await?;
+    let second = rx_out.recv_async().await?;
+    assert!(String::from_utf8_lossy(&first).contains("\"result\""));
+    assert!(String::from_utf8_lossy(&second).contains("-32600"));
+    mock.assert_async().await;
+    Ok(())
+}
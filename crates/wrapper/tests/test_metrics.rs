@@ -0,0 +1,49 @@
+use mcp_stdio_wrapper::metrics::{Metrics, record_retry};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tests counters, retry attribution and latency percentiles.
+/// # Errors
+/// Returns an error if the snapshot file can not be written or parsed.
+/// # Panics
+/// Panics if a counter is wrong.
+#[tokio::test]
+pub async fn test_metrics_snapshot() -> Result<(), Box<dyn std::error::Error>> {
+    let metrics = Arc::new(Metrics::new(2));
+    let worker = metrics.worker(1);
+    for ms in 1..=100 {
+        worker.record(Duration::from_millis(ms), ms % 10 == 0);
+    }
+    worker.record_rejected();
+    Arc::clone(&worker)
+        .scope(async {
+            record_retry();
+            record_retry();
+        })
+        .await;
+    // outside a worker task retries are not attributed
+    record_retry();
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.requests, 101);
+    assert_eq!(snapshot.errors, 11);
+    assert_eq!(snapshot.retries, 2);
+    assert_eq!(snapshot.workers[0].requests, 0);
+    let latency = &snapshot.workers[1].latency_ms;
+    assert_eq!(
+        (latency.p50, latency.p90, latency.p99, latency.max),
+        (50, 90, 99, 100)
+    );
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("metrics.jsonl");
+    metrics.write_snapshot(&path)?;
+    metrics.write_snapshot(&path)?;
+    let content = std::fs::read_to_string(&path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let parsed: Value = serde_json::from_str(lines[1])?;
+    assert_eq!(parsed["workers"][1]["retries"], 2);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+
+const TOKEN: &str = r#"{"access_token":"t1","token_type":"Bearer","expires_in":3600}"#;
+
+fn oauth_config(url: &str, token_url: &str) -> Config {
+    Config::from_cli([
+        "test",
+        "--url",
+        url,
+        "--oauth-token-url",
+        token_url,
+        "--oauth-client-id",
+        "wrapper",
+        "--oauth-client-secret",
+        "s3cr3t",
+        "--oauth-scopes",
+        "mcp:read mcp:write",
+    ])
+}
+
+/// Tests that the token is fetched once and reused for subsequent requests.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the mock server does not receive the expected requests.
+#[tokio::test]
+pub async fn test_oauth_token_cached() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp/", server.url());
+    let token_url = format!("{}/token", server.url());
+
+    let token_mock = server
+        .mock("POST", "/token")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("grant_type".into(), "client_credentials".into()),
+            Matcher::UrlEncoded("client_id".into(), "wrapper".into()),
+            Matcher::UrlEncoded("scope".into(), "mcp:read mcp:write".into()),
+        ]))
+        .with_status(200)
+        .with_body(TOKEN)
+        .expect(1)
+        .create_async()
+        .await;
+    let mcp_mock = server
+        .mock("POST", "/mcp/")
+        .match_header("authorization", "Bearer t1")
+        .with_status(200)
+        .with_body("")
+        .expect(2)
+        .create_async()
+        .await;
+
+    let config = oauth_config(&url, &token_url);
+    assert!(!format!("{config:?}").contains("s3cr3t"));
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+    assert!(cli.is_auth());
+
+    cli.stream_post(&http_client, Bytes::from("{}")).await?;
+    cli.stream_post(&http_client, Bytes::from("{}")).await?;
+    token_mock.assert_async().await;
+    mcp_mock.assert_async().await;
+    Ok(())
+}
+
+/// Tests that a 401 triggers exactly one token refresh and retry.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the mock server does not receive the expected requests.
+#[tokio::test]
+pub async fn test_oauth_refresh_on_unauthorized() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp/", server.url());
+    let token_url = format!("{}/token", server.url());
+
+    let token_mock = server
+        .mock("POST", "/token")
+        .with_status(200)
+        .with_body(TOKEN)
+        .expect(2)
+        .create_async()
+        .await;
+    let mcp_mock = server
+        .mock("POST", "/mcp/")
+        .with_status(401)
+        .with_body("expired")
+        .expect(2)
+        .create_async()
+        .await;
+
+    let config = oauth_config(&url, &token_url);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let out = cli.stream_post(&http_client, Bytes::from("{}")).await;
+    assert!(out.is_err());
+    token_mock.assert_async().await;
+    mcp_mock.assert_async().await;
+    Ok(())
+}
+
+/// Tests that a token endpoint error body is bounded by `--max-response-bytes`.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the oversized error body ends up in the error.
+#[tokio::test]
+pub async fn test_oauth_error_body_bounded() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp/", server.url());
+    let token_url = format!("{}/token", server.url());
+
+    let _token_mock = server
+        .mock("POST", "/token")
+        .with_status(500)
+        .with_body("x".repeat(1024))
+        .create_async()
+        .await;
+
+    let mut config = oauth_config(&url, &token_url);
+    config.max_response_bytes = 100;
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let err = cli
+        .stream_post(&http_client, Bytes::from("{}"))
+        .await
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_default();
+    assert!(
+        err.contains("--max-response-bytes limit of 100 bytes"),
+        "{err}"
+    );
+    assert!(!err.contains(&"x".repeat(101)), "{err}");
+    Ok(())
+}
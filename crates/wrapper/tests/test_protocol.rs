@@ -0,0 +1,143 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::protocol::{
+    downgrade_initialize, negotiate, sends_version_header, supported_versions,
+};
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+#[test]
+fn negotiates_highest_common_version() {
+    let server = vec!["2024-11-05".to_string(), "2025-03-26".to_string()];
+    assert_eq!(
+        negotiate("2025-11-25", &server).as_deref(),
+        Some("2025-03-26")
+    );
+    assert_eq!(
+        negotiate("2024-11-05", &server).as_deref(),
+        Some("2024-11-05")
+    );
+    assert_eq!(negotiate("2024-01-01", &server), None);
+    // versions unknown to the wrapper are only taken when the client asked for them
+    assert_eq!(negotiate("2025-01-01", &["2024-12-01".to_string()]), None);
+}
+
+#[test]
+fn reads_supported_versions_from_error() {
+    let error = json!({"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Unsupported protocol version","data":{"supported":["2025-03-26"],"requested":"2025-11-25"}}});
+    assert_eq!(
+        supported_versions(&error),
+        Some(vec!["2025-03-26".to_string()])
+    );
+    assert_eq!(
+        supported_versions(&json!({"jsonrpc":"2.0","id":1,"result":{}})),
+        None
+    );
+}
+
+#[test]
+fn downgrade_drops_newer_capabilities() {
+    let request = json!({"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{"elicitation":{},"roots":{}}}});
+    let old = downgrade_initialize(&request, "2025-03-26");
+    assert_eq!(old["params"]["protocolVersion"], "2025-03-26");
+    assert_eq!(old["params"]["capabilities"], json!({"roots":{}}));
+    assert!(sends_version_header("2025-06-18"));
+    assert!(!sends_version_header("2025-03-26"));
+}
+
+/// Tests that a refused initialize is retried with a version the server supports.
+/// # Errors
+/// * client construction or request fails
+#[tokio::test]
+async fn initialize_is_downgraded() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let refused = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("2025-11-25".into()))
+        .with_status(400)
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Unsupported protocol version","data":{"supported":["2024-11-05","2025-03-26"]}}}"#)
+        .create_async()
+        .await;
+    let accepted = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("2025-03-26".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{}}}"#)
+        .create_async()
+        .await;
+    let list = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("tools/list".into()))
+        .match_header("mcp-protocol-version", Matcher::Missing)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}"#)
+        .create_async()
+        .await;
+
+    let url = format!("{}/mcp", server.url());
+    let client = McpStreamClient::try_new(Config::from_cli(["test", "--url", url.as_str()]))?;
+    let http = reqwest::Client::new();
+    let res = client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-11-25","capabilities":{"elicitation":{}}}}"#),
+        )
+        .await?;
+    assert!(String::from_utf8_lossy(&res.messages()[0]).contains("2025-03-26"));
+    assert_eq!(client.protocol_version().as_deref(), Some("2025-03-26"));
+
+    client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#),
+        )
+        .await?;
+    refused.assert_async().await;
+    accepted.assert_async().await;
+    list.assert_async().await;
+    Ok(())
+}
+
+/// Tests that the negotiated version is sent as a header when the version has it.
+/// # Errors
+/// * client construction or request fails
+#[tokio::test]
+async fn version_header_follows_initialize() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let _init = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("\"initialize\"".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{}}}"#)
+        .create_async()
+        .await;
+    let ping = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("ping".into()))
+        .match_header("mcp-protocol-version", "2025-06-18")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{}}"#)
+        .create_async()
+        .await;
+
+    let url = format!("{}/mcp", server.url());
+    let client = McpStreamClient::try_new(Config::from_cli(["test", "--url", url.as_str()]))?;
+    let http = reqwest::Client::new();
+    client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{}}}"#),
+        )
+        .await?;
+    client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#),
+        )
+        .await?;
+    ping.assert_async().await;
+    Ok(())
+}
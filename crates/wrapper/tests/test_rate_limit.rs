@@ -0,0 +1,30 @@
+use mcp_stdio_wrapper::rate_limit::{RateLimiter, parse_rate};
+use std::time::{Duration, Instant};
+
+/// Tests rate parsing.
+/// # Panics
+/// Panics if a rate is parsed wrongly.
+#[test]
+pub fn test_parse_rate() {
+    assert_eq!(parse_rate("10/s"), Ok(10.0));
+    assert_eq!(parse_rate("5"), Ok(5.0));
+    assert_eq!(parse_rate("120/m"), Ok(2.0));
+    assert_eq!(parse_rate("3600/h"), Ok(1.0));
+    for bad in ["", "0/s", "-1/s", "ten/s", "10/d"] {
+        assert!(parse_rate(bad).is_err(), "{bad}");
+    }
+}
+
+/// Tests that requests beyond the burst are delayed.
+/// # Panics
+/// Panics if the limiter lets requests through too early.
+#[tokio::test]
+pub async fn test_rate_limiter() {
+    let limiter = RateLimiter::new(20.0);
+    let start = Instant::now();
+    for _ in 0..22 {
+        limiter.acquire().await;
+    }
+    // 20 tokens of burst, two more at 50ms each
+    assert!(start.elapsed() >= Duration::from_millis(90));
+}
@@ -38,3 +38,34 @@ async fn test_reader() {
         let _ = handle.await;
     }
 }
+
+/// Tests that oversized lines are answered with an error instead of forwarded.
+/// # Panics
+/// Panics if a line is forwarded or rejected wrongly.
+#[tokio::test]
+async fn test_limited_reader() {
+    use mcp_stdio_wrapper::stdio_reader::{rejected_reply, spawn_limited_reader};
+
+    let (tx, rx) = flume::unbounded::<Bytes>();
+    let input: &[u8] = b"{\"id\":1}\r\n{\"id\":2,\"params\":\"xxxxxxxxxxxxxxxx\"}\nlast";
+
+    let handle = spawn_limited_reader(tx, input, 16);
+    let _ = handle.await;
+
+    // the error reply keeps its place between the forwarded lines
+    let lines: Vec<Bytes> = rx.drain().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], Bytes::from("{\"id\":1}"));
+    assert_eq!(lines[2], Bytes::from("last"));
+    assert!(rejected_reply(&lines[0]).is_none());
+
+    let errors: Vec<Bytes> = rejected_reply(&lines[1]).into_iter().collect();
+    assert_eq!(errors.len(), 1);
+    let error = String::from_utf8_lossy(&errors[0]);
+    assert!(error.contains("\"id\":2"), "{error}");
+    assert!(error.contains("-32600"), "{error}");
+    assert!(
+        error.contains("--max-line-bytes limit of 16 bytes"),
+        "{error}"
+    );
+}
@@ -0,0 +1,98 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::record::Recorder;
+use mcp_stdio_wrapper::replay::Replay;
+use serde_json::Value;
+
+fn recording() -> tempfile::NamedTempFile {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let recorder = Recorder::open(file.path()).unwrap();
+    recorder.record(
+        br#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        &[Bytes::from(
+            r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"a"}]}}"#,
+        )],
+    );
+    recorder.record(
+        br#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"a","arguments":{"token":"s3cr3t"},"_meta":{"progressToken":7}}}"#,
+        &[
+            Bytes::from(r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{"progress":1}}"#),
+            Bytes::from(r#"{"jsonrpc":"2.0","id":2,"result":{"content":[]}}"#),
+        ],
+    );
+    file
+}
+
+fn parse(line: &Bytes) -> Value {
+    serde_json::from_slice(line).unwrap()
+}
+
+#[test]
+fn recording_is_redacted() {
+    let file = recording();
+    let raw = std::fs::read_to_string(file.path()).unwrap();
+    assert_eq!(raw.lines().count(), 2);
+    assert!(!raw.contains("s3cr3t"), "{raw}");
+    for line in raw.lines() {
+        serde_json::from_str::<Value>(line).unwrap();
+    }
+}
+
+#[test]
+fn replay_answers_with_new_ids() {
+    let file = recording();
+    let replay = Replay::load(file.path()).unwrap();
+    assert_eq!(replay.len(), 2);
+
+    let out = replay.answer(br#"{"jsonrpc":"2.0","id":"x","method":"tools/list"}"#);
+    assert_eq!(out.len(), 1);
+    assert_eq!(parse(&out[0])["id"], "x");
+    assert_eq!(parse(&out[0])["result"]["tools"][0]["name"], "a");
+
+    // `_meta` differs between runs and is ignored
+    let out = replay.answer(
+        br#"{"jsonrpc":"2.0","id":9,"method":"tools/call","params":{"name":"a","arguments":{"token":"[REDACTED]"},"_meta":{"progressToken":1}}}"#,
+    );
+    assert_eq!(out.len(), 2);
+    assert_eq!(parse(&out[0])["method"], "notifications/progress");
+    assert_eq!(parse(&out[1])["id"], 9);
+}
+
+#[test]
+fn replay_reports_unknown_requests() {
+    let file = recording();
+    let replay = Replay::load(file.path()).unwrap();
+    let out = replay.answer(br#"{"jsonrpc":"2.0","id":3,"method":"prompts/list"}"#);
+    assert_eq!(parse(&out[0])["error"]["code"], -32601);
+    assert!(
+        replay
+            .answer(br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .is_empty()
+    );
+}
+
+#[test]
+fn replay_answers_batches() {
+    let file = recording();
+    let replay = Replay::load(file.path()).unwrap();
+    let out = replay.answer(
+        br#"[{"jsonrpc":"2.0","id":5,"method":"tools/list"},{"jsonrpc":"2.0","id":6,"method":"prompts/list"}]"#,
+    );
+    assert_eq!(out.len(), 1);
+    let batch = parse(&out[0]);
+    assert_eq!(batch[0]["id"], 5);
+    assert_eq!(batch[1]["error"]["code"], -32601);
+}
+
+/// # Errors
+/// * in-memory IO fails
+#[tokio::test]
+async fn replay_serves_lines() -> std::io::Result<()> {
+    let file = recording();
+    let replay = Replay::load(file.path())?;
+    let input = b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/list\"}\n\n".as_slice();
+    let mut output = Vec::new();
+    replay.serve(input, &mut output).await?;
+    let text = String::from_utf8_lossy(&output);
+    assert_eq!(text.lines().count(), 1, "{text}");
+    Ok(())
+}
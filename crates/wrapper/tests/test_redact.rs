@@ -0,0 +1,54 @@
+use mcp_stdio_wrapper::redact::{REDACTED, Redactor};
+
+fn redactor() -> Redactor {
+    Redactor::new(&["session_key".to_string()])
+}
+
+#[test]
+fn redacts_authorization_headers() {
+    let r = redactor();
+    let out = r.redact(r#"headers: {"authorization": "Bearer abc.def", "accept": "*/*"}"#);
+    assert!(!out.contains("abc.def"), "{out}");
+    assert!(out.contains(r#""accept": "*/*""#), "{out}");
+
+    let out = r.redact("Authorization: Basic dXNlcjpwYXNz");
+    assert_eq!(out, format!("Authorization: {REDACTED}"));
+}
+
+#[test]
+fn redacts_bearer_tokens_anywhere() {
+    let out = redactor().redact("retry 1/2: sent Bearer eyJhbGciOi.x-y_z= upstream");
+    assert_eq!(out, format!("retry 1/2: sent Bearer {REDACTED} upstream"));
+}
+
+#[test]
+fn redacts_tokens_in_urls() {
+    let r = redactor();
+    let out = r.redact(
+        "error sending request for url (https://user:pw@gw.example/mcp?access_token=s3cr3t&x=1)",
+    );
+    assert!(!out.contains("s3cr3t"), "{out}");
+    assert!(!out.contains("user:pw"), "{out}");
+    assert!(out.contains("&x=1"), "{out}");
+}
+
+#[test]
+fn redacts_default_and_configured_fields() {
+    let r = redactor();
+    let out = r.redact(r#"{"password":"hunter2","session_key":"k1","max_tokens":5}"#);
+    assert!(!out.contains("hunter2"), "{out}");
+    assert!(!out.contains("k1"), "{out}");
+    assert!(out.contains(r#""max_tokens":5"#), "{out}");
+}
+
+#[test]
+fn redacts_escaped_json_in_log_records() {
+    let out = redactor().redact(r#"{"message":"sent {\"token\":\"abc\"}"}"#);
+    assert!(!out.contains("abc"), "{out}");
+}
+
+#[test]
+fn leaves_clean_text_borrowed() {
+    let out = redactor().redact("Upstream request failed: 503");
+    assert!(matches!(out, std::borrow::Cow::Borrowed(_)));
+}
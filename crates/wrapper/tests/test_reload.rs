@@ -0,0 +1,72 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::reload::{apply_reload, remember_args};
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+use std::ffi::OsString;
+
+/// Tests that a reload picks up changed headers from the config file
+/// and keeps the session.
+/// # Errors
+/// * config file, client or request fails
+#[tokio::test]
+async fn reload_replaces_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let file = tempfile::NamedTempFile::new()?;
+    std::fs::write(file.path(), "header = [\"X-Token: old\"]\n")?;
+
+    let args: Vec<OsString> = ["test", "--url", url.as_str(), "--config"]
+        .into_iter()
+        .map(OsString::from)
+        .chain([file.path().as_os_str().to_owned()])
+        .collect();
+    remember_args(&args);
+    let client = McpStreamClient::try_new(Config::from_cli(args))?;
+    client.set_session_id("s1");
+    let http = reqwest::Client::new();
+
+    let old = server
+        .mock("POST", "/mcp")
+        .match_header("x-token", "old")
+        .match_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .create_async()
+        .await;
+    client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#),
+        )
+        .await?;
+    old.assert_async().await;
+
+    std::fs::write(file.path(), "header = [\"X-Token: new\"]\n")?;
+    apply_reload(&client)?;
+
+    let new = server
+        .mock("POST", "/mcp")
+        .match_header("x-token", "new")
+        .match_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{}}"#)
+        .create_async()
+        .await;
+    client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#),
+        )
+        .await?;
+    new.assert_async().await;
+
+    // a broken file keeps the running config
+    std::fs::write(file.path(), "header = [\"no colon\"]\n")?;
+    assert!(apply_reload(&client).is_err());
+    Ok(())
+}
+
+#[test]
+fn try_from_cli_reports_errors() {
+    assert!(Config::try_from_cli(["test"]).is_err());
+    assert!(Config::try_from_cli(["test", "--url", "http://localhost/mcp"]).is_ok());
+}
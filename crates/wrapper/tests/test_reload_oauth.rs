@@ -0,0 +1,65 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::reload::{apply_reload, remember_args};
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+use std::ffi::OsString;
+
+/// Tests that a reload rebuilds the OAuth client with the new credentials.
+/// # Errors
+/// * config file, client or request fails
+#[tokio::test]
+async fn reload_replaces_oauth_client() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let token_url = format!("{}/token", server.url());
+    let oauth_file = |id: &str| {
+        format!(
+            "oauth_token_url = \"{token_url}\"\noauth_client_id = \"{id}\"\noauth_client_secret = \"s\"\n"
+        )
+    };
+    let file = tempfile::NamedTempFile::new()?;
+    std::fs::write(file.path(), oauth_file("old"))?;
+
+    let args: Vec<OsString> = ["test", "--url", url.as_str(), "--config"]
+        .into_iter()
+        .map(OsString::from)
+        .chain([file.path().as_os_str().to_owned()])
+        .collect();
+    remember_args(&args);
+    let client = McpStreamClient::try_new(Config::from_cli(args))?;
+    let http = reqwest::Client::new();
+
+    let old_token = server
+        .mock("POST", "/token")
+        .match_body(Matcher::UrlEncoded("client_id".into(), "old".into()))
+        .with_body(r#"{"access_token":"t1","expires_in":3600}"#)
+        .expect(0)
+        .create_async()
+        .await;
+    let new_token = server
+        .mock("POST", "/token")
+        .match_body(Matcher::UrlEncoded("client_id".into(), "new".into()))
+        .with_body(r#"{"access_token":"t2","expires_in":3600}"#)
+        .create_async()
+        .await;
+    let mcp = server
+        .mock("POST", "/mcp")
+        .match_header("authorization", "Bearer t2")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .create_async()
+        .await;
+
+    std::fs::write(file.path(), oauth_file("new"))?;
+    apply_reload(&client)?;
+    client
+        .stream_post(
+            &http,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#),
+        )
+        .await?;
+    old_token.assert_async().await;
+    new_token.assert_async().await;
+    mcp.assert_async().await;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::reload::{ReloadSet, apply_reload_all, remember_args};
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+use std::ffi::OsString;
+use std::sync::Arc;
+
+/// Tests that one reload reaches every live session of a `--listen` wrapper.
+/// # Errors
+/// * config file, client or request fails
+#[tokio::test]
+async fn reload_set_reaches_all_sessions() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let file = tempfile::NamedTempFile::new()?;
+    std::fs::write(file.path(), "header = [\"X-Token: old\"]\n")?;
+
+    let args: Vec<OsString> = ["test", "--url", url.as_str(), "--config"]
+        .into_iter()
+        .map(OsString::from)
+        .chain([file.path().as_os_str().to_owned()])
+        .collect();
+    remember_args(&args);
+    let set = ReloadSet::default();
+    let clients = [
+        Arc::new(McpStreamClient::try_new(Config::from_cli(args.clone()))?),
+        Arc::new(McpStreamClient::try_new(Config::from_cli(args.clone()))?),
+    ];
+    for client in &clients {
+        set.add(client);
+    }
+    // a closed session is skipped
+    set.add(&Arc::new(McpStreamClient::try_new(Config::from_cli(args))?));
+
+    std::fs::write(file.path(), "header = [\"X-Token: new\"]\n")?;
+    apply_reload_all(&set)?;
+
+    let new = server
+        .mock("POST", "/mcp")
+        .match_header("x-token", "new")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .expect(2)
+        .create_async()
+        .await;
+    let http = reqwest::Client::new();
+    for client in &clients {
+        client
+            .stream_post(
+                &http,
+                Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#),
+            )
+            .await?;
+    }
+    new.assert_async().await;
+    Ok(())
+}
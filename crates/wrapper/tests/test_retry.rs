@@ -0,0 +1,90 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::post_error::PostError;
+use mcp_stdio_wrapper::retry::{RetryPolicy, is_idempotent};
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+fn status(code: u16) -> PostError {
+    PostError::Status {
+        status: StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        body: String::new(),
+    }
+}
+
+/// Tests retry decisions and backoff bounds.
+/// # Panics
+/// Panics if the policy does not match the configuration.
+#[test]
+pub fn test_retry_policy() {
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        "http://localhost/mcp",
+        "--retry-base-ms",
+        "100",
+        "--retry-max-ms",
+        "300",
+    ]);
+    let policy = RetryPolicy::from_config(&config);
+    assert_eq!(policy.max_retries, 2);
+    assert_eq!(policy.statuses, vec![429, 502, 503, 504]);
+
+    assert!(policy.idempotent_only);
+
+    let transport = PostError::Transport("reset".to_string());
+    assert!(policy.should_retry(0, &transport, Some("tools/list")));
+    assert!(!policy.should_retry(0, &transport, Some("tools/call")));
+    assert!(!policy.should_retry(0, &transport, None));
+    assert!(policy.should_retry(1, &status(503), Some("ping")));
+    assert!(!policy.should_retry(2, &status(503), Some("ping")));
+    assert!(!policy.should_retry(0, &status(500), Some("ping")));
+    assert!(!policy.should_retry(0, &PostError::from("bad"), Some("ping")));
+
+    for (attempt, lo, hi) in [(0, 50, 100), (1, 100, 200), (5, 150, 300)] {
+        let d = policy.delay(attempt);
+        assert!(d >= Duration::from_millis(lo) && d <= Duration::from_millis(hi));
+    }
+
+    let lenient = RetryPolicy::from_config(&Config::from_cli([
+        "test",
+        "--url",
+        "http://localhost/mcp",
+        "--retry-non-idempotent",
+    ]));
+    assert!(lenient.should_retry(0, &transport, Some("tools/call")));
+    assert!(lenient.should_retry(0, &transport, None));
+    assert!(is_idempotent("ping"));
+    assert!(!is_idempotent("resources/subscribe"));
+}
+
+/// Tests that a retryable status is re-sent until retries are exhausted.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the request count does not match.
+#[tokio::test]
+pub async fn test_post_with_retry() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let mock = server
+        .mock("POST", "/mcp")
+        .with_status(503)
+        .with_body("busy")
+        .expect(3)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--retry-base-ms", "1"]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let payload = Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#);
+    let out = cli.post_with_retry(&http_client, payload).await;
+    assert!(matches!(out, Err(PostError::Status { status, .. }) if status.as_u16() == 503));
+    mock.assert_async().await;
+    Ok(())
+}
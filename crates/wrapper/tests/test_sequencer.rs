@@ -0,0 +1,42 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::sequencer::Sequencer;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tests that a request finishing early waits for the ones read before it.
+/// # Errors
+/// Returns an error if channel operations fail.
+/// # Panics
+/// Panics if the output order differs from the input order.
+#[tokio::test]
+pub async fn test_sequencer_order() -> Result<(), Box<dyn std::error::Error>> {
+    let (tx_in, rx_in) = flume::unbounded();
+    let (tx_out, rx_out) = flume::unbounded();
+    for line in ["slow", "fast"] {
+        tx_in.send_async(Bytes::from(line)).await?;
+    }
+    drop(tx_in);
+
+    let sequencer = Arc::new(Sequencer::default());
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        let (seq, rx, tx) = (Arc::clone(&sequencer), rx_in.clone(), tx_out.clone());
+        handles.push(tokio::spawn(async move {
+            while let Ok((ticket, line)) = seq.recv(&rx).await {
+                if line == "slow" {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+                let _turn = seq.turn(ticket).await;
+                let _ = tx.send_async(line).await;
+            }
+        }));
+    }
+    drop(tx_out);
+    for handle in handles {
+        handle.await?;
+    }
+
+    let out: Vec<Bytes> = rx_out.drain().collect();
+    assert_eq!(out, vec![Bytes::from("slow"), Bytes::from("fast")]);
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::session_store::load_session;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+
+const INIT: &str =
+    r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18"}}"#;
+const INIT_RESULT: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"serverInfo":{"name":"gw"}}}"#;
+const LIST: &str = r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#;
+
+/// Tests that a saved session is resumed and replaced when the server rejects it.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the session is not saved, resumed or re-initialized.
+#[tokio::test]
+pub async fn test_session_resume() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("session.json");
+    let args = [
+        "test",
+        "--url",
+        url.as_str(),
+        "--session-file",
+        file.to_str().ok_or("path")?,
+    ];
+
+    // first run negotiates and saves the session
+    let init = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("initialize".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_body(INIT_RESULT)
+        .create_async()
+        .await;
+    let config = Config::from_cli(args);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+    cli.stream_post(&http_client, Bytes::from(INIT)).await?;
+    init.assert_async().await;
+    init.remove_async().await;
+    let saved = load_session(&file).ok_or("session not saved")?;
+    assert_eq!(saved.session_id, "s1");
+
+    // restart: initialize is answered locally
+    let cli = McpStreamClient::try_new(Config::from_cli(args))?;
+    assert_eq!(cli.get_session_id().as_deref(), Some("s1"));
+    let out = cli.stream_post(&http_client, Bytes::from(INIT)).await?;
+    let messages = out.messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&messages[0])?,
+        serde_json::from_str::<serde_json::Value>(INIT_RESULT)?
+    );
+
+    // expired session: re-initialize and repeat the request
+    let expired = server
+        .mock("POST", "/mcp")
+        .match_header("mcp-session-id", "s1")
+        .with_status(404)
+        .create_async()
+        .await;
+    let init = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("\"initialize\"".into()))
+        .with_header("mcp-session-id", "s2")
+        .with_body(INIT_RESULT)
+        .create_async()
+        .await;
+    let initialized = server
+        .mock("POST", "/mcp")
+        .match_header("mcp-session-id", "s2")
+        .match_body(Matcher::Regex("notifications/initialized".into()))
+        .with_status(202)
+        .create_async()
+        .await;
+    let list = server
+        .mock("POST", "/mcp")
+        .match_header("mcp-session-id", "s2")
+        .match_body(Matcher::Regex("tools/list".into()))
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}"#)
+        .create_async()
+        .await;
+
+    let out = cli.stream_post(&http_client, Bytes::from(LIST)).await?;
+    assert_eq!(out.messages().len(), 1);
+    for mock in [expired, init, initialized, list] {
+        mock.assert_async().await;
+    }
+    assert_eq!(cli.get_session_id().as_deref(), Some("s2"));
+    assert_eq!(load_session(&file).ok_or("missing")?.session_id, "s2");
+    Ok(())
+}
+
+/// Tests that an initialize with a new clientInfo starts a fresh session
+/// instead of reusing or replaying the saved one.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the stale session id is sent or the old initialize is replayed.
+#[tokio::test]
+pub async fn test_session_resume_changed_client() -> Result<(), Box<dyn std::error::Error>> {
+    const INIT_V1: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"clientInfo":{"name":"c","version":"1"}}}"#;
+    const INIT_V2: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"clientInfo":{"name":"c","version":"2"}}}"#;
+
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("session.json");
+    let args = [
+        "test",
+        "--url",
+        url.as_str(),
+        "--session-file",
+        file.to_str().ok_or("path")?,
+    ];
+
+    let first = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("\"version\":\"1\"".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_body(INIT_RESULT)
+        .expect(1)
+        .create_async()
+        .await;
+    let config = Config::from_cli(args);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+    cli.stream_post(&http_client, Bytes::from(INIT_V1)).await?;
+
+    // restart with a newer client: no stale session id, no replay of version 1
+    let cli = McpStreamClient::try_new(Config::from_cli(args))?;
+    let stale = server
+        .mock("POST", "/mcp")
+        .match_header("mcp-session-id", "s1")
+        .expect(0)
+        .create_async()
+        .await;
+    let second = server
+        .mock("POST", "/mcp")
+        .match_header("mcp-session-id", Matcher::Missing)
+        .match_body(Matcher::Regex("\"version\":\"2\"".into()))
+        .with_header("mcp-session-id", "s2")
+        .with_body(INIT_RESULT)
+        .create_async()
+        .await;
+    cli.stream_post(&http_client, Bytes::from(INIT_V2)).await?;
+
+    first.assert_async().await;
+    stale.assert_async().await;
+    second.assert_async().await;
+    assert_eq!(cli.get_session_id().as_deref(), Some("s2"));
+    let saved = load_session(&file).ok_or("missing")?;
+    assert_eq!(saved.session_id, "s2");
+    assert_eq!(
+        saved.initialize_request["params"]["clientInfo"]["version"],
+        "2"
+    );
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+
+/// Tests that `--auth-token` is sent as a bearer token and
+/// `--auth-header-name` switches it to an API key header.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the mock server does not receive the expected header.
+#[tokio::test]
+pub async fn test_streamer_auth_token() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp/", server.url());
+
+    let cases: [(&[&str], &str, &str); 2] = [
+        (&["--auth-token", "abc"], "authorization", "Bearer abc"),
+        (
+            &["--auth-token", "abc", "--auth-header-name", "X-API-Key"],
+            "x-api-key",
+            "abc",
+        ),
+    ];
+
+    for (flags, header, expected) in cases {
+        let mock = server
+            .mock("POST", "/mcp/")
+            .match_header(header, expected)
+            .with_status(200)
+            .with_body("")
+            .create_async()
+            .await;
+
+        let mut args = vec!["test", "--url", url.as_str()];
+        args.extend_from_slice(flags);
+        let config = Config::from_cli(args);
+        assert!(!format!("{config:?}").contains("abc"));
+
+        let http_client = get_http_client(&config).await?;
+        let cli = McpStreamClient::try_new(config)?;
+        assert!(cli.is_auth());
+
+        cli.stream_post(&http_client, Bytes::from("{}")).await?;
+        mock.assert_async().await;
+        mock.remove_async().await;
+    }
+    Ok(())
+}
@@ -68,3 +68,57 @@ pub async fn test_streamer_cert_invalid_certificate() {
         "unexpected invalid-cert error: {err}"
     );
 }
+
+/// Tests that an unreadable client key is reported with its path.
+/// # Panics
+/// Panics if the HTTP client is built without the key.
+#[tokio::test]
+pub async fn test_streamer_client_key_missing() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cert = temp_dir.path().join("client.pem");
+    std::fs::write(&cert, "").unwrap();
+    let key = temp_dir.path().join("missing.key");
+
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        "https://localhost:3000/mcp",
+        "--client-cert",
+        cert.to_str().unwrap(),
+        "--client-key",
+        key.to_str().unwrap(),
+    ]);
+
+    let err = get_http_client(&config)
+        .await
+        .expect_err("missing key should fail");
+    assert!(err.contains("missing.key"), "unexpected error: {err}");
+}
+
+/// Tests that a client certificate without a key is rejected.
+/// # Panics
+/// Panics if the HTTP client is built with an invalid identity.
+#[tokio::test]
+pub async fn test_streamer_client_identity_invalid() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cert = temp_dir.path().join("client.pem");
+    std::fs::write(&cert, "not a certificate").unwrap();
+
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        "https://localhost:3000/mcp",
+        "--client-cert",
+        cert.to_str().unwrap(),
+        "--insecure-skip-verify",
+    ]);
+    assert!(config.insecure);
+
+    let err = get_http_client(&config)
+        .await
+        .expect_err("invalid identity should fail");
+    assert!(
+        err.contains("Invalid client identity"),
+        "unexpected error: {err}"
+    );
+}
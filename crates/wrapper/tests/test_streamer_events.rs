@@ -0,0 +1,61 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::push_forwarder::spawn_push_forwarder;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::Server;
+use std::sync::Arc;
+use std::time::Duration;
+
+const INIT: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#;
+const NOTIFY: &str = r#"{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}"#;
+
+/// Tests that server-initiated messages on the GET stream are forwarded
+/// and that a 405 ends the stream task.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the notification is not forwarded or the task keeps running.
+#[tokio::test]
+pub async fn test_event_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let _init = server
+        .mock("POST", "/mcp")
+        .with_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .create_async()
+        .await;
+    let stream = server
+        .mock("GET", "/mcp")
+        .match_header("mcp-session-id", "s1")
+        .with_header("content-type", "text/event-stream")
+        .with_body(format!("event: message\ndata: {NOTIFY}\n\n"))
+        .expect_at_least(1)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str()]);
+    let http_client = get_http_client(&config).await?;
+    let cli = Arc::new(McpStreamClient::try_new(config)?);
+    let (tx, rx) = flume::unbounded();
+    let _push = spawn_push_forwarder(&cli, tx);
+    let handles = cli.spawn_event_stream(http_client.clone());
+
+    cli.stream_post(&http_client, Bytes::from(INIT)).await?;
+    let pushed = tokio::time::timeout(Duration::from_secs(5), rx.recv_async()).await??;
+    assert_eq!(pushed, Bytes::from(NOTIFY));
+    stream.assert_async().await;
+    stream.remove_async().await;
+
+    // the stream reconnects after the server closed it, now without GET support
+    let _rejected = server
+        .mock("GET", "/mcp")
+        .with_status(405)
+        .create_async()
+        .await;
+    for handle in handles {
+        tokio::time::timeout(Duration::from_secs(5), handle).await??;
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::push_forwarder::spawn_push_forwarder;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{}}}"#;
+
+/// Tests that repeated outages of the primary move the session to the next url.
+/// # Errors
+/// * client construction or a request to the standby fails
+/// # Panics
+/// * requests are not routed to the standby or no notification is emitted
+#[tokio::test]
+pub async fn test_streamer_failover() -> Result<(), Box<dyn std::error::Error>> {
+    let mut primary = Server::new_async().await;
+    let mut standby = Server::new_async().await;
+
+    let down = primary
+        .mock("POST", "/mcp")
+        .with_status(503)
+        .expect(2)
+        .create_async()
+        .await;
+    let init = standby
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(json!({"method": "initialize"})))
+        .with_header("mcp-session-id", "standby-1")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{}}}"#)
+        .create_async()
+        .await;
+    let list = standby
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(json!({"method": "tools/list"})))
+        .match_header("mcp-session-id", "standby-1")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}"#)
+        .create_async()
+        .await;
+
+    let primary_url = format!("{}/mcp", primary.url());
+    let standby_url = format!("{}/mcp", standby.url());
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        primary_url.as_str(),
+        "--url",
+        standby_url.as_str(),
+        "--failover",
+        "--failover-after",
+        "2",
+    ]);
+    let http = reqwest::Client::new();
+    let cli = Arc::new(McpStreamClient::try_new(config)?);
+    assert!(cli.is_failover());
+    let (tx, rx) = flume::unbounded();
+    let _push = spawn_push_forwarder(&cli, tx);
+
+    // the first outage is returned, the second one switches
+    assert!(
+        cli.stream_post(&http, Bytes::from(INITIALIZE))
+            .await
+            .is_err()
+    );
+    let res = cli.stream_post(&http, Bytes::from(INITIALIZE)).await?;
+    assert!(String::from_utf8_lossy(&res.messages()[0]).contains("2025-06-18"));
+
+    let note: Value = serde_json::from_slice(&rx.recv_async().await?)?;
+    assert_eq!(note["method"], "notifications/message");
+    assert_eq!(note["params"]["data"]["event"], "failover");
+
+    cli.stream_post(
+        &http,
+        Bytes::from(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#),
+    )
+    .await?;
+    down.assert_async().await;
+    init.assert_async().await;
+    list.assert_async().await;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mcp_stdio_wrapper::streamer_headers::parse_header;
+use mockito::Server;
+
+#[test]
+pub fn test_parse_header() {
+    let (name, value) = parse_header("X-Tenant:  acme ").expect("valid header");
+    assert_eq!(name.as_str(), "x-tenant");
+    assert_eq!(value.to_str().unwrap(), "acme");
+
+    let (_, value) = parse_header("Cookie: a=1; b=2").expect("value keeps separators");
+    assert_eq!(value.to_str().unwrap(), "a=1; b=2");
+
+    assert!(parse_header("no-separator").is_err());
+    let err = parse_header("Bearer s3cr3t-value").expect_err("missing separator");
+    assert!(!err.contains("s3cr3t-value"), "{err}");
+    assert!(parse_header("bad name: x").is_err());
+}
+
+/// Tests that repeated `--header` flags are sent with every request.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the mock server does not receive the expected headers.
+#[tokio::test]
+pub async fn test_streamer_custom_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp/", server.url());
+
+    let mock = server
+        .mock("POST", "/mcp/")
+        .match_header("x-tenant", "acme")
+        .match_header("x-trace", "1")
+        .with_status(200)
+        .with_body("")
+        .create_async()
+        .await;
+
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        url.as_str(),
+        "--header",
+        "X-Tenant: acme",
+        "--header",
+        "X-Trace: 1",
+    ]);
+    let rendered = format!("{config:?}");
+    assert!(rendered.contains("X-Tenant"));
+    assert!(!rendered.contains("acme"));
+
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+    cli.stream_post(&http_client, Bytes::from("{}")).await?;
+    mock.assert_async().await;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mockito::{Matcher, Server};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tests that an idle session is pinged and traffic postpones the ping.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if no ping reaches the server.
+#[tokio::test]
+pub async fn test_keepalive() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+    let _init = server
+        .mock("POST", "/mcp")
+        .match_body(Matcher::Regex("initialize".into()))
+        .with_header("mcp-session-id", "s1")
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        .create_async()
+        .await;
+    let ping = server
+        .mock("POST", "/mcp")
+        .match_header("mcp-session-id", "s1")
+        .match_body(Matcher::Regex("\"ping\"".into()))
+        .with_body(r#"{"jsonrpc":"2.0","id":"keepalive","result":{}}"#)
+        .expect_at_least(1)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str()]);
+    let http_client = get_http_client(&config).await?;
+    let cli = Arc::new(McpStreamClient::try_new(config)?);
+    let handles = cli.spawn_keepalive(http_client.clone(), Duration::from_millis(100));
+
+    let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#;
+    cli.stream_post(&http_client, Bytes::from(init)).await?;
+    assert!(cli.idle_for() < Duration::from_millis(100));
+
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    ping.assert_async().await;
+    for handle in handles {
+        handle.abort();
+    }
+    Ok(())
+}
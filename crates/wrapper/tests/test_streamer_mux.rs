@@ -0,0 +1,153 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mcp_stdio_wrapper::streamer_mux::{split_url_prefix, upstream_prefix};
+use mockito::{Matcher, Server};
+use serde_json::{Value, json};
+
+#[test]
+pub fn test_split_url_prefix() {
+    assert_eq!(
+        split_url_prefix("time=http://localhost/mcp"),
+        (Some("time"), "http://localhost/mcp")
+    );
+    assert_eq!(
+        split_url_prefix("http://localhost/mcp?a=b"),
+        (None, "http://localhost/mcp?a=b")
+    );
+    assert_eq!(upstream_prefix("http://localhost/mcp", 1), "s2");
+    assert_eq!(upstream_prefix("time=http://localhost/mcp", 1), "time");
+}
+
+/// Tests that tools/list is merged with prefixes and tools/call is routed by prefix.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the responses are not merged or routed as expected.
+#[tokio::test]
+pub async fn test_streamer_mux() -> Result<(), Box<dyn std::error::Error>> {
+    let mut time = Server::new_async().await;
+    let mut files = Server::new_async().await;
+
+    let list_time = time
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(json!({"method": "tools/list"})))
+        .with_status(200)
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"now"}]}}"#)
+        .create_async()
+        .await;
+    let list_files = files
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(json!({"method": "tools/list"})))
+        .with_status(200)
+        .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"read"}]}}"#)
+        .create_async()
+        .await;
+    let call_files = files
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(
+            json!({"method": "tools/call", "params": {"name": "read"}}),
+        ))
+        .with_status(200)
+        .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{"content":[]}}"#)
+        .create_async()
+        .await;
+
+    let time_url = format!("time={}/mcp", time.url());
+    let files_url = format!("{}/mcp", files.url());
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        time_url.as_str(),
+        "--url",
+        files_url.as_str(),
+    ]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+    assert!(cli.is_mux());
+
+    let list = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#;
+    let out = cli.stream_post(&http_client, Bytes::from(list)).await?;
+    let merged: Value = serde_json::from_slice(&out.messages()[0])?;
+    assert_eq!(
+        merged["result"]["tools"],
+        json!([{"name": "time__now"}, {"name": "s2__read"}])
+    );
+
+    let call = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"s2__read"}}"#;
+    let out = cli.stream_post(&http_client, Bytes::from(call)).await?;
+    assert_eq!(
+        out.messages(),
+        vec![Bytes::from(
+            r#"{"jsonrpc":"2.0","id":2,"result":{"content":[]}}"#
+        )]
+    );
+
+    let unknown = r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"x__y"}}"#;
+    assert!(
+        cli.stream_post(&http_client, Bytes::from(unknown))
+            .await
+            .is_err()
+    );
+
+    list_time.assert_async().await;
+    list_files.assert_async().await;
+    call_files.assert_async().await;
+    Ok(())
+}
+
+/// Tests that the answer to a server-initiated request goes back to the
+/// upstream that sent it.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the answer reaches the wrong upstream.
+#[tokio::test]
+pub async fn test_streamer_mux_server_request() -> Result<(), Box<dyn std::error::Error>> {
+    let mut time = Server::new_async().await;
+    let mut files = Server::new_async().await;
+
+    let call_files = files
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(json!({"method": "tools/call"})))
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(concat!(
+            "data: {\"jsonrpc\":\"2.0\",\"id\":\"srv-1\",\"method\":\"sampling/createMessage\"}\n\n",
+            "data: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"content\":[]}}\n\n",
+        ))
+        .create_async()
+        .await;
+    let answer_files = files
+        .mock("POST", "/mcp")
+        .match_body(Matcher::PartialJson(json!({"id": "srv-1", "result": {}})))
+        .with_status(202)
+        .create_async()
+        .await;
+    let answer_time = time.mock("POST", "/mcp").expect(0).create_async().await;
+
+    let time_url = format!("{}/mcp", time.url());
+    let files_url = format!("{}/mcp", files.url());
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        time_url.as_str(),
+        "--url",
+        files_url.as_str(),
+    ]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let call = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"s2__read"}}"#;
+    let out = cli.stream_post(&http_client, Bytes::from(call)).await?;
+    assert_eq!(out.messages().len(), 2);
+
+    let answer = r#"{"jsonrpc":"2.0","id":"srv-1","result":{}}"#;
+    cli.stream_post(&http_client, Bytes::from(answer)).await?;
+
+    call_files.assert_async().await;
+    answer_files.assert_async().await;
+    answer_time.assert_async().await;
+    Ok(())
+}
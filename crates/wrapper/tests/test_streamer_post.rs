@@ -29,7 +29,7 @@ pub async fn test_streamer_post() -> Result<(), Box<dyn std::error::Error>> {
 
     let mock_init = server
         .mock("POST", "/mcp/")
-        .match_header("authorization", "token")
+        .match_header("authorization", "Bearer not-a-real-secret")
         .match_header("content-type", "application/json")
         .with_status(200)
         .with_header("content-type", "text/event-stream")
@@ -40,7 +40,7 @@ pub async fn test_streamer_post() -> Result<(), Box<dyn std::error::Error>> {
 
     let mock_notify = server
         .mock("POST", "/mcp/")
-        .match_header("authorization", "token")
+        .match_header("authorization", "Bearer not-a-real-secret")
         .match_header("mcp-session-id", "9cb62a01-2523-4380-964e-2e3efd1d135a")
         .with_status(202)
         .with_body("")
@@ -49,21 +49,16 @@ pub async fn test_streamer_post() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut authorization_header = DEFAULT_AUTH;
     if authorization_header.is_none() {
-        authorization_header = Some("token");
+        authorization_header = Some("Bearer not-a-real-secret");
     }
 
-    let config = Config::from_cli([
-        "test",
-        "--url",
-        &format!("{url}/mcp/"),
-        "--auth",
-        authorization_header.unwrap(),
-    ]);
-    assert!(!format!("{config:?}").contains("token"));
+    let secret = authorization_header.unwrap();
+    let config = Config::from_cli(["test", "--url", &format!("{url}/mcp/"), "--auth", secret]);
+    assert!(!format!("{config:?}").contains(secret));
 
     let http_client = get_http_client(&config).await.map_err(|e| e.clone())?;
     let cli = McpStreamClient::try_new(config)?;
-    assert!(!format!("{cli:?}").contains("token"));
+    assert!(!format!("{cli:?}").contains(secret));
 
     let out = cli.stream_post(&http_client, Bytes::from(INIT)).await?;
     mock_init.assert_async().await;
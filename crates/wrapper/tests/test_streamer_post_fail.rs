@@ -27,3 +27,113 @@ pub async fn test_streamer_post() -> Result<(), Box<dyn std::error::Error>> {
     mock_init.assert_async().await;
     Ok(())
 }
+
+/// # Panics
+/// # Errors
+/// on test failure
+#[tokio::test]
+pub async fn test_streamer_post_too_large() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--max-response-bytes", "100"]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    // error responses are bounded as well
+    for status in [200, 500] {
+        let mock = server
+            .mock("POST", "/mcp")
+            .with_status(status)
+            .with_body("x".repeat(1024))
+            .create_async()
+            .await;
+        let out = cli.stream_post(&http_client, Bytes::from("{}")).await;
+        let err = out.err().map(|e| e.to_string()).unwrap_or_default();
+        assert!(
+            err.contains("--max-response-bytes limit of 100 bytes"),
+            "{status}: {err}"
+        );
+        mock.assert_async().await;
+        mock.remove_async().await;
+    }
+    Ok(())
+}
+
+/// # Panics
+/// # Errors
+/// on test failure
+#[tokio::test]
+pub async fn test_streamer_post_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    use mcp_stdio_wrapper::post_error::PostError;
+    use std::time::Duration;
+
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+
+    let _mock = server
+        .mock("POST", "/mcp")
+        .with_status(200)
+        .with_chunked_body(|w| {
+            w.write_all(b"data: {}\n")?;
+            w.flush()?;
+            std::thread::sleep(Duration::from_millis(2500));
+            w.write_all(b"data: {}\n")
+        })
+        .create_async()
+        .await;
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--request-timeout", "1"]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let out = cli
+        .stream_post_timeout(&http_client, Bytes::from("{}"))
+        .await;
+    assert_eq!(out.err(), Some(PostError::Timeout(Duration::from_secs(1))));
+    Ok(())
+}
+
+/// `--request-timeout` longer than `--timeout` is not cut short by it
+/// # Panics
+/// # Errors
+/// on test failure
+#[tokio::test]
+pub async fn test_streamer_request_timeout_above_timeout() -> Result<(), Box<dyn std::error::Error>>
+{
+    use std::time::Duration;
+
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+
+    let _mock = server
+        .mock("POST", "/mcp")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_chunked_body(|w| {
+            std::thread::sleep(Duration::from_millis(1500));
+            w.write_all(br#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
+        })
+        .create_async()
+        .await;
+    let config = Config::from_cli([
+        "test",
+        "--url",
+        url.as_str(),
+        "--timeout",
+        "1",
+        "--request-timeout",
+        "3",
+    ]);
+    assert_eq!(config.response_timeout(), Duration::from_secs(3));
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let out = cli
+        .stream_post_timeout(
+            &http_client,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#),
+        )
+        .await?;
+    assert_eq!(out.messages().len(), 1);
+    Ok(())
+}
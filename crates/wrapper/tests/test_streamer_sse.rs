@@ -0,0 +1,186 @@
+use bytes::Bytes;
+use mcp_stdio_wrapper::config::Config;
+use mcp_stdio_wrapper::http_client::get_http_client;
+use mcp_stdio_wrapper::post_error::PostError;
+use mcp_stdio_wrapper::sse_events::{SseEvent, SseParser};
+use mcp_stdio_wrapper::streamer::McpStreamClient;
+use mcp_stdio_wrapper::streamer_sse::{legacy_sse_url, sse_endpoint};
+use mockito::Server;
+use std::time::Duration;
+
+const REQUEST: &str = r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#;
+const RESPONSE: &str = r#"{"jsonrpc":"2.0","id":7,"result":{"tools":[]}}"#;
+
+#[test]
+pub fn test_legacy_sse_url() {
+    assert_eq!(
+        legacy_sse_url("http://localhost:4444/servers/abc/mcp"),
+        "http://localhost:4444/servers/abc/sse"
+    );
+    assert_eq!(
+        legacy_sse_url("http://localhost:4444/servers/abc/mcp/"),
+        "http://localhost:4444/servers/abc/sse"
+    );
+    assert_eq!(
+        legacy_sse_url("http://localhost:4444/custom"),
+        "http://localhost:4444/custom/sse"
+    );
+}
+
+#[test]
+pub fn test_sse_endpoint_origin() {
+    let sse = "http://localhost:4444/servers/abc/sse";
+    assert_eq!(
+        sse_endpoint(sse, "/messages?session_id=1").ok().as_deref(),
+        Some("http://localhost:4444/messages?session_id=1")
+    );
+    assert!(sse_endpoint(sse, "http://localhost:4444/messages").is_ok());
+    for foreign in [
+        "http://evil.example/messages",
+        "//evil.example/messages",
+        "https://localhost:4444/messages",
+        "http://localhost:4445/messages",
+    ] {
+        assert!(sse_endpoint(sse, foreign).is_err(), "{foreign}");
+    }
+}
+
+#[test]
+pub fn test_sse_parser() {
+    let mut parser = SseParser::default();
+    assert_eq!(parser.feed(&Bytes::from("event: endpoint")), None);
+    assert_eq!(
+        parser.feed(&Bytes::from("data: /messages?session_id=1")),
+        Some(SseEvent {
+            event: "endpoint".to_string(),
+            data: Bytes::from("/messages?session_id=1"),
+        })
+    );
+    assert_eq!(parser.feed(&Bytes::from(": keep-alive")), None);
+    assert_eq!(
+        parser.feed(&Bytes::from("data:{}")),
+        Some(SseEvent {
+            event: "message".to_string(),
+            data: Bytes::from("{}"),
+        })
+    );
+}
+
+/// Tests that a 405 from the streamable endpoint switches to the legacy SSE transport.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+/// # Panics
+/// Panics if the response is not delivered through the event stream.
+#[tokio::test]
+pub async fn test_streamer_sse_fallback() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+
+    let mock_mcp = server
+        .mock("POST", "/mcp")
+        .with_status(405)
+        .create_async()
+        .await;
+    let mock_sse = server
+        .mock("GET", "/sse")
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_chunked_body(|w| {
+            w.write_all(b"event: endpoint\ndata: /messages?session_id=1\n\n")?;
+            w.flush()?;
+            std::thread::sleep(Duration::from_millis(300));
+            w.write_all(format!("event: message\ndata: {RESPONSE}\n\n").as_bytes())
+        })
+        .create_async()
+        .await;
+    let mock_message = server
+        .mock("POST", "/messages?session_id=1")
+        .with_status(202)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str()]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let out = cli.stream_post(&http_client, Bytes::from(REQUEST)).await?;
+    assert!(cli.is_sse());
+    assert_eq!(out.out, vec![Bytes::from(RESPONSE)]);
+
+    mock_mcp.assert_async().await;
+    mock_sse.assert_async().await;
+    mock_message.assert_async().await;
+    Ok(())
+}
+
+/// Tests that a failed legacy SSE connect does not switch the transport for good.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+#[tokio::test]
+pub async fn test_streamer_sse_fallback_connect_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+
+    let _mock_mcp = server
+        .mock("POST", "/mcp")
+        .with_status(405)
+        .create_async()
+        .await;
+    let _mock_sse = server
+        .mock("GET", "/sse")
+        .with_status(404)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str()]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    assert!(
+        cli.stream_post(&http_client, Bytes::from(REQUEST))
+            .await
+            .is_err()
+    );
+    assert!(!cli.is_sse());
+    Ok(())
+}
+
+/// Tests that a response never sent on the event stream times out after `--timeout`.
+/// # Errors
+/// Returns an error if the mock server setup fails.
+#[tokio::test]
+pub async fn test_streamer_sse_response_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new_async().await;
+    let url = format!("{}/mcp", server.url());
+
+    let _mock_mcp = server
+        .mock("POST", "/mcp")
+        .with_status(405)
+        .create_async()
+        .await;
+    let _mock_sse = server
+        .mock("GET", "/sse")
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_chunked_body(|w| {
+            w.write_all(b"event: endpoint\ndata: /messages?session_id=1\n\n")?;
+            w.flush()?;
+            std::thread::sleep(Duration::from_secs(3));
+            Ok(())
+        })
+        .create_async()
+        .await;
+    let _mock_message = server
+        .mock("POST", "/messages?session_id=1")
+        .with_status(202)
+        .create_async()
+        .await;
+
+    let config = Config::from_cli(["test", "--url", url.as_str(), "--timeout", "1"]);
+    let http_client = get_http_client(&config).await?;
+    let cli = McpStreamClient::try_new(config)?;
+
+    let out = cli.stream_post(&http_client, Bytes::from(REQUEST)).await;
+    assert!(matches!(out, Err(PostError::Timeout(_))), "{out:?}");
+    Ok(())
+}